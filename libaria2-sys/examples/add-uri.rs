@@ -1,13 +1,15 @@
 use libaria2_sys::{
     ffi::{KeyVal, SessionConfigFfi},
-    A2Gid, RunMode,
+    A2Gid, EventCallback, RunMode,
 };
+use std::{cell::Cell, rc::Rc};
 
 fn main() {
     unsafe {
         libaria2_sys::ffi::library_init();
 
-        let mut event_counter = 0;
+        let event_counter = Rc::new(Cell::new(0));
+        let callback_counter = event_counter.clone();
 
         let session = libaria2_sys::ffi::session_new(
             &vec![KeyVal {
@@ -17,14 +19,12 @@ fn main() {
             &SessionConfigFfi {
                 keep_running: false,
                 use_signal_handler: false,
-                user_data: (&mut event_counter) as *mut i32 as usize,
             },
-            |_, event, gid, counter| {
+            EventCallback::new(move |_, event, gid| {
                 println!("[Event] {:?} gid {:x}", event, gid);
-                let counter = counter as *mut i32;
-                counter.write(*counter + 1);
+                callback_counter.set(callback_counter.get() + 1);
                 0
-            },
+            }),
         );
         println!("Create session: {}", session.is_valid());
 
@@ -63,7 +63,7 @@ fn main() {
         }
 
         loop {
-            event_counter = 0;
+            event_counter.set(0);
             match libaria2_sys::ffi::run(session, RunMode::RUN_ONCE) {
                 0 => break,
                 1 => {
@@ -75,7 +75,7 @@ fn main() {
                 }
                 _ => unreachable!(),
             }
-            println!("Events this loop: {}", event_counter);
+            println!("Events this loop: {}", event_counter.get());
         }
 
         libaria2_sys::ffi::session_final(session);
@@ -66,6 +66,40 @@ namespace aria2 {
             std::vector<UriDataWrapper> uris;
         };
 
+        struct BtMetaInfoDataWrapper {
+        public:
+            inline explicit BtMetaInfoDataWrapper(aria2::BtMetaInfoData data) : metaInfo(std::move(data)) {
+            }
+
+            inline unsigned int getNumAnnounceTiers() const {
+                return static_cast<unsigned int>(metaInfo.announceList.size());
+            }
+
+            inline const std::vector<std::string>& getAnnounceTier(unsigned int index) const {
+                assert(index < metaInfo.announceList.size());
+                return metaInfo.announceList[index];
+            }
+
+            inline const std::string& getComment() const {
+                return metaInfo.comment;
+            }
+
+            inline int64_t getCreationDate() const {
+                return static_cast<int64_t>(metaInfo.creationDate);
+            }
+
+            inline aria2::BtFileMode getMode() const {
+                return metaInfo.mode;
+            }
+
+            inline const std::string& getName() const {
+                return metaInfo.name;
+            }
+
+        private:
+            aria2::BtMetaInfoData metaInfo;
+        };
+
         struct DownloadHandleWrapper {
         public:
             inline explicit DownloadHandleWrapper(aria2::DownloadHandle* handle) : handle(handle) {
@@ -160,8 +194,8 @@ namespace aria2 {
                 return filePtr;
             }
 
-            inline std::unique_ptr<aria2::BtMetaInfoData> getBtMetaInfo() const {
-                return std::make_unique<aria2::BtMetaInfoData>(handle->getBtMetaInfo());
+            inline std::unique_ptr<BtMetaInfoDataWrapper> getBtMetaInfo() const {
+                return std::make_unique<BtMetaInfoDataWrapper>(handle->getBtMetaInfo());
             }
 
             inline const std::string& getOption(rust::Str name) const {
@@ -16,7 +16,7 @@ namespace aria2 {
         SessionHandle sessionNew(
                 const RKeyVals& rustOptions,
                 const SessionConfigFfi& config,
-                const rust::Fn<int(SessionHandle s, DownloadEvent e, A2Gid g, size_t user)> cb);
+                rust::Box<EventCallback> cb);
 
         int sessionFinal(SessionHandle session);
 
@@ -36,13 +36,13 @@ namespace aria2 {
         int addUri(SessionHandle session, A2Gid& gid, const rust::Vec<rust::String>& uris,
                     const RKeyVals& options, int position);
 
-        int addMetalink(SessionHandle session, rust::Vec<A2Gid>& gids, const rust::Str metalinkFile,
+        int addMetalink(SessionHandle session, rust::Vec<A2Gid>& gids, rust::Slice<const uint8_t> metalinkFile,
                          const RKeyVals& options, int position);
 
-        int addTorrent(SessionHandle session, A2Gid& gid, const rust::Str torrentFile,
+        int addTorrent(SessionHandle session, A2Gid& gid, rust::Slice<const uint8_t> torrentFile,
                         const RKeyVals& options, int position);
 
-        int addTorrentWithWebseedUris(SessionHandle session, A2Gid& gid, const rust::Str torrentFile,
+        int addTorrentWithWebseedUris(SessionHandle session, A2Gid& gid, rust::Slice<const uint8_t> torrentFile,
                                           const rust::Vec<rust::String>& webSeedUris, const RKeyVals& options, int position);
 
         // Download control
@@ -56,13 +56,14 @@ namespace aria2 {
         // Options
 
         int changeOption(SessionHandle session, A2Gid gid, const RKeyVals& options);
-        rust::Str getGlobalOption(SessionHandle session, const rust::Str name);
+        rust::String getGlobalOption(SessionHandle session, const rust::Str name);
         RKeyVals getGlobalOptions(SessionHandle session);
         int changeGlobalOption(SessionHandle session, const RKeyVals& options);
 
         // Stats
 
         GlobalStat getGlobalStat(SessionHandle session);
+        rust::Vec<DownloadStatsFfi> getDownloadStats(SessionHandle session, rust::Slice<const A2Gid> gids);
 
         // Download Handle
 
@@ -74,5 +75,6 @@ namespace aria2 {
         int __eventCallbackDelegate(Session* session, DownloadEvent event, A2Gid gid, void* userData);
         void __convertKeyVals(const RKeyVals& src, aria2::KeyVals& dst);
         void __convertKeyValsBack(const aria2::KeyVals& src, RKeyVals& dst);
+        std::string __toPath(rust::Slice<const uint8_t> path);
     }
 }
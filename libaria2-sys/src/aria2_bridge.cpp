@@ -9,32 +9,41 @@
 
 namespace aria2 {
     namespace bridge {
-        static rust::Fn<int(SessionHandle s, DownloadEvent e, A2Gid g, size_t user)> EVENT_RUST_CALLBACK;
-
         // Session
         // <editor-fold>
 
         SessionHandle sessionNew(
                 const RKeyVals& rustOptions,
                 const SessionConfigFfi& rustConfig,
-                const rust::Fn<int(SessionHandle s, DownloadEvent e, A2Gid g, size_t user)> eventCallback
+                rust::Box<EventCallback> eventCallback
         ) {
             aria2::KeyVals options;
             __convertKeyVals(rustOptions, options);
 
+            // The callback is owned by the session from now on, it is freed in sessionFinal.
+            EventCallback* callback = eventCallback.into_raw();
+
             SessionConfig config;
             config.keepRunning = rustConfig.keep_running;
             config.useSignalHandler = rustConfig.use_signal_handler;
             config.downloadEventCallback = &aria2::bridge::__eventCallbackDelegate;
-            config.userData = (void*) rustConfig.user_data;
+            config.userData = (void*) callback;
 
-            EVENT_RUST_CALLBACK = eventCallback;
+            Session* session = sessionNew(options, config);
+            if (session == nullptr) {
+                rust::Box<EventCallback>::from_raw(callback);
+                return {.ptr = 0, .callback = 0};
+            }
 
-            return {.ptr = (size_t) sessionNew(options, config)};
+            return {.ptr = (size_t) session, .callback = (size_t) callback};
         }
 
         int sessionFinal(SessionHandle session) {
-            return aria2::sessionFinal((Session*) session.ptr);
+            int res = aria2::sessionFinal((Session*) session.ptr);
+            if (session.callback != 0) {
+                rust::Box<EventCallback>::from_raw((EventCallback*) session.callback);
+            }
+            return res;
         }
 
         // </editor-fold>
@@ -71,8 +80,8 @@ namespace aria2 {
         int addUri(SessionHandle session, A2Gid& gid, const rust::Vec<rust::String>& rustUris,
                    const RKeyVals& rustOptions, int position) {
             std::vector<std::string> uris;
-            uris.resize(rustUris.size());
-            for (auto uri : rustUris) {
+            uris.reserve(rustUris.size());
+            for (const auto& uri : rustUris) {
                 uris.emplace_back(uri);
             }
 
@@ -82,14 +91,14 @@ namespace aria2 {
             return aria2::addUri((Session*) session.ptr, &gid, uris, options, position);
         }
 
-        int addMetalink(SessionHandle session, rust::Vec<A2Gid>& gids, const rust::Str metalinkFile,
+        int addMetalink(SessionHandle session, rust::Vec<A2Gid>& gids, rust::Slice<const uint8_t> metalinkFile,
                         const RKeyVals& rustOptions, int position) {
             aria2::KeyVals options;
             __convertKeyVals(rustOptions, options);
 
             std::vector<A2Gid> resGuids;
             int res = aria2::addMetalink(
-                    (Session*) session.ptr, &resGuids, std::string(metalinkFile), options, position
+                    (Session*) session.ptr, &resGuids, __toPath(metalinkFile), options, position
             );
             for (auto guid : resGuids) {
                 gids.push_back(guid);
@@ -98,15 +107,15 @@ namespace aria2 {
             return res;
         }
 
-        int addTorrent(SessionHandle session, A2Gid& gid, const rust::Str torrentFile,
+        int addTorrent(SessionHandle session, A2Gid& gid, rust::Slice<const uint8_t> torrentFile,
                        const RKeyVals& rustOptions, int position) {
             aria2::KeyVals options;
             __convertKeyVals(rustOptions, options);
 
-            return aria2::addTorrent((Session*) session.ptr, &gid, std::string(torrentFile), options, position);
+            return aria2::addTorrent((Session*) session.ptr, &gid, __toPath(torrentFile), options, position);
         }
 
-        int addTorrentWithWebseedUris(SessionHandle session, A2Gid& gid, const rust::Str torrentFile,
+        int addTorrentWithWebseedUris(SessionHandle session, A2Gid& gid, rust::Slice<const uint8_t> torrentFile,
                                       const rust::Vec<rust::String>& rustWebSeedUris, const RKeyVals& rustOptions,
                                       int position) {
             aria2::KeyVals options;
@@ -114,12 +123,12 @@ namespace aria2 {
 
             std::vector<std::string> webSeedUris;
             webSeedUris.reserve(rustWebSeedUris.size());
-            for (auto uri : rustWebSeedUris) {
+            for (const auto& uri : rustWebSeedUris) {
                 webSeedUris.emplace_back(uri);
             }
 
             return aria2::addTorrent(
-                    (Session*) session.ptr, &gid, std::string(torrentFile), webSeedUris, options, position
+                    (Session*) session.ptr, &gid, __toPath(torrentFile), webSeedUris, options, position
             );
         }
         // </editor-fold>
@@ -163,8 +172,9 @@ namespace aria2 {
             return aria2::changeOption((Session*) session.ptr, gid, options);
         }
 
-        rust::Str getGlobalOption(SessionHandle session, const rust::Str name) {
-            return rust::Str(aria2::getGlobalOption((Session*) session.ptr, std::string(name)));
+        rust::String getGlobalOption(SessionHandle session, const rust::Str name) {
+            // aria2 returns the option by value, it must be copied before the temporary is destroyed.
+            return rust::String(aria2::getGlobalOption((Session*) session.ptr, std::string(name)));
         }
 
         RKeyVals getGlobalOptions(SessionHandle session) {
@@ -196,6 +206,33 @@ namespace aria2 {
             };
         }
 
+        rust::Vec<DownloadStatsFfi> getDownloadStats(SessionHandle session, rust::Slice<const A2Gid> gids) {
+            rust::Vec<DownloadStatsFfi> stats;
+            stats.reserve(gids.size());
+            for (A2Gid gid : gids) {
+                aria2::DownloadHandle* handle = aria2::getDownloadHandle((Session*) session.ptr, gid);
+                if (handle == nullptr) {
+                    continue;
+                }
+
+                stats.push_back(
+                        {
+                                .gid = gid,
+                                .status = handle->getStatus(),
+                                .total_len = handle->getTotalLength(),
+                                .completed_len = handle->getCompletedLength(),
+                                .upload_len = handle->getUploadLength(),
+                                .download_speed = handle->getDownloadSpeed(),
+                                .upload_speed = handle->getUploadSpeed(),
+                                .connections = handle->getConnections(),
+                                .error_code = handle->getErrorCode(),
+                        }
+                );
+                aria2::deleteDownloadHandle(handle);
+            }
+            return stats;
+        }
+
         // Download Handle
         // <editor-fold>
 
@@ -225,11 +262,11 @@ namespace aria2 {
         // <editor-fold>
 
         int __eventCallbackDelegate(Session* session, DownloadEvent event, A2Gid gid, void* userData) {
-            return EVENT_RUST_CALLBACK(
-                    {.ptr = (size_t) session},
+            EventCallback* callback = (EventCallback*) userData;
+            return callback->call(
+                    {.ptr = (size_t) session, .callback = (size_t) userData},
                     event,
-                    gid,
-                    (size_t) userData
+                    gid
             );
         }
 
@@ -252,6 +289,11 @@ namespace aria2 {
             }
         }
 
+        std::string __toPath(rust::Slice<const uint8_t> path) {
+            // Paths are passed as raw bytes so they don't need to be valid UTF-8.
+            return std::string((const char*) path.data(), path.size());
+        }
+
         // </editor-fold>
     }
 }
@@ -8,6 +8,22 @@ pub use cxx;
 pub type A2Gid = u64;
 pub type RunMode = ffi::RUN_MODE;
 
+/// Closure called by aria2 for every download event, it lives as long as the session.
+pub struct EventCallback(Box<dyn FnMut(SessionHandle, ffi::DownloadEvent, A2Gid) -> i32>);
+
+impl EventCallback {
+    pub fn new<F>(callback: F) -> Box<Self>
+    where
+        F: FnMut(SessionHandle, ffi::DownloadEvent, A2Gid) -> i32 + 'static,
+    {
+        Box::new(Self(Box::new(callback)))
+    }
+
+    fn call(&mut self, session: SessionHandle, event: ffi::DownloadEvent, gid: A2Gid) -> i32 {
+        (self.0)(session, event, gid)
+    }
+}
+
 #[rustfmt::skip]
 #[cxx::bridge(namespace = "aria2::bridge")]
 pub mod ffi {
@@ -15,6 +31,8 @@ pub mod ffi {
     pub struct SessionHandle {
         // Not really a usize but a `*mut Session`
         ptr: usize,
+        // Not really a usize but a `*mut EventCallback`, owned by the session
+        callback: usize,
     }
 
     #[repr(u32)]
@@ -30,7 +48,6 @@ pub mod ffi {
     pub struct SessionConfigFfi {
         pub keep_running: bool,
         pub use_signal_handler: bool,
-        pub user_data: usize,
     }
 
     pub struct KeyVal {
@@ -53,6 +70,20 @@ pub mod ffi {
         pub num_stopped: i32,
     }
 
+    /// Stats of a single download, filled in bulk by `get_download_stats`.
+    #[derive(Copy, Clone)]
+    pub struct DownloadStatsFfi {
+        pub gid: u64,
+        pub status: DownloadStatus,
+        pub total_len: i64,
+        pub completed_len: i64,
+        pub upload_len: i64,
+        pub download_speed: i32,
+        pub upload_speed: i32,
+        pub connections: i32,
+        pub error_code: i32,
+    }
+
     #[repr(u32)]
     pub enum OffsetMode {
         OFFSET_MODE_SET,
@@ -83,6 +114,11 @@ pub mod ffi {
         DOWNLOAD_REMOVED,
     }
 
+    extern "Rust" {
+        type EventCallback;
+        fn call(self: &mut EventCallback, session: SessionHandle, event: DownloadEvent, gid: u64) -> i32;
+    }
+
     unsafe extern "C++" {
         include!("libaria2-sys/include/aria2_bridge.hpp");
         include!("libaria2-sys/include/DownloadHandleWrapper.hpp");
@@ -103,8 +139,6 @@ pub mod ffi {
         type DownloadStatus;
         #[namespace = "aria2"]
         type UriData;
-        #[namespace = "aria2"]
-        type BtMetaInfoData;
 
         #[namespace = "aria2"]
         #[cxx_name = "libraryInit"]
@@ -117,7 +151,7 @@ pub mod ffi {
         pub unsafe fn session_new(
             options: &Vec<KeyVal>,
             config: &SessionConfigFfi,
-            cb: fn(SessionHandle, DownloadEvent, u64, usize) -> i32,
+            cb: Box<EventCallback>,
         ) -> SessionHandle;
 
         #[cxx_name = "sessionFinal"]
@@ -148,7 +182,7 @@ pub mod ffi {
         pub unsafe fn add_metalink(
             session: SessionHandle,
             gids: &mut Vec<u64>,
-            metalink_file: &str,
+            metalink_file: &[u8],
             options: &Vec<KeyVal>,
             position: i32
         ) -> i32;
@@ -157,7 +191,7 @@ pub mod ffi {
         pub unsafe fn add_torrent(
             session: SessionHandle,
             gid: &mut u64,
-            torrent_file: &str,
+            torrent_file: &[u8],
             options: &Vec<KeyVal>,
             position: i32,
         ) -> i32;
@@ -166,7 +200,7 @@ pub mod ffi {
         pub unsafe fn add_torrent_with_webseed_uris(
             session: SessionHandle,
             gid: &mut u64,
-            torrent_file: &str,
+            torrent_file: &[u8],
             webseed_uris: &Vec<String>,
             options: &Vec<KeyVal>,
             position: i32,
@@ -186,7 +220,7 @@ pub mod ffi {
         #[cxx_name = "changeOption"]
         pub unsafe fn change_option(session: SessionHandle, gid: u64, options: &Vec<KeyVal>) -> i32;
         #[cxx_name = "getGlobalOption"]
-        pub unsafe fn get_global_option(session: SessionHandle, name: &str) -> &str;
+        pub unsafe fn get_global_option(session: SessionHandle, name: &str) -> String;
         #[cxx_name = "getGlobalOptions"]
         pub unsafe fn get_global_options(session: SessionHandle) -> Vec<KeyVal>;
         #[cxx_name = "changeGlobalOption"]
@@ -194,6 +228,9 @@ pub mod ffi {
 
         #[cxx_name = "getGlobalStat"]
         pub unsafe fn get_global_stat(session: SessionHandle) -> GlobalStat;
+        /// Stats of every download of `gids` that still exists, in the same order.
+        #[cxx_name = "getDownloadStats"]
+        pub unsafe fn get_download_stats(session: SessionHandle, gids: &[u64]) -> Vec<DownloadStatsFfi>;
 
         type UriDataWrapper;
         #[cxx_name = "getUri"]
@@ -215,6 +252,20 @@ pub mod ffi {
         #[cxx_name = "getUris"]
         pub unsafe fn uris(self: &FileDataWrapper) -> &CxxVector<UriDataWrapper>;
 
+        type BtMetaInfoDataWrapper;
+        #[cxx_name = "getNumAnnounceTiers"]
+        pub unsafe fn num_announce_tiers(self: &BtMetaInfoDataWrapper) -> u32;
+        #[cxx_name = "getAnnounceTier"]
+        pub unsafe fn announce_tier(self: &BtMetaInfoDataWrapper, index: u32) -> &CxxVector<CxxString>;
+        #[cxx_name = "getComment"]
+        pub unsafe fn comment(self: &BtMetaInfoDataWrapper) -> &CxxString;
+        #[cxx_name = "getCreationDate"]
+        pub unsafe fn creation_date(self: &BtMetaInfoDataWrapper) -> i64;
+        #[cxx_name = "getMode"]
+        pub unsafe fn mode(self: &BtMetaInfoDataWrapper) -> BtFileMode;
+        #[cxx_name = "getName"]
+        pub unsafe fn name(self: &BtMetaInfoDataWrapper) -> &CxxString;
+
         type DownloadHandleWrapper;
         #[cxx_name = "getStatus"]
         pub unsafe fn status(self: &DownloadHandleWrapper) -> DownloadStatus;
@@ -255,7 +306,7 @@ pub mod ffi {
         #[cxx_name = "getFile"]
         pub unsafe fn get_file(self: &DownloadHandleWrapper, index: u32) -> UniquePtr<FileDataWrapper>;
         #[cxx_name = "getBtMetaInfo"]
-        pub unsafe fn bt_meta_info(self: &DownloadHandleWrapper) -> UniquePtr<BtMetaInfoData>;
+        pub unsafe fn bt_meta_info(self: &DownloadHandleWrapper) -> UniquePtr<BtMetaInfoDataWrapper>;
         #[cxx_name = "getOption"]
         pub unsafe fn get_option<'a>(self: &'a DownloadHandleWrapper, name: &str) -> &'a CxxString;
         #[cxx_name = "getOptions"]
@@ -301,3 +352,14 @@ impl Debug for ffi::DownloadEvent {
         }
     }
 }
+
+impl Debug for ffi::BtFileMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::BT_FILE_MODE_NONE => f.write_str("BT_FILE_MODE_NONE"),
+            Self::BT_FILE_MODE_SINGLE => f.write_str("BT_FILE_MODE_SINGLE"),
+            Self::BT_FILE_MODE_MULTI => f.write_str("BT_FILE_MODE_MULTI"),
+            _ => unreachable!(),
+        }
+    }
+}
@@ -64,9 +64,8 @@ pub unsafe fn get_session() -> SessionHandle {
         &SessionConfigFfi {
             keep_running: false,
             use_signal_handler: false,
-            user_data: 0,
         },
-        |_, _, _, _| 0,
+        EventCallback::new(|_, _, _| 0),
     )
 }
 
@@ -253,6 +252,38 @@ fn download_handle() {
     })
 }
 
+#[test]
+fn download_stats() {
+    test_harness(|| unsafe {
+        library_init();
+        let session = get_session();
+
+        let mut gid = 0;
+        let res = add_uri(
+            session,
+            &mut gid,
+            &vec!["http://localhost/1".into()],
+            &vec![],
+            -1,
+        );
+        assert_eq!(res, 0);
+
+        // Unknown GIDs are skipped.
+        let stats = get_download_stats(session, &[gid, gid + 1]);
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].gid, gid);
+        assert_eq!(stats[0].status, DownloadStatus::DOWNLOAD_WAITING);
+        assert_eq!(stats[0].completed_len, 0);
+        assert_eq!(stats[0].error_code, 0);
+
+        assert!(get_download_stats(session, &[]).is_empty());
+
+        shutdown(session, true);
+        session_final(session);
+        library_deinit();
+    })
+}
+
 #[test]
 fn multiple_session() {
     test_harness(|| unsafe {
@@ -281,3 +312,84 @@ fn multiple_session() {
         library_deinit();
     })
 }
+
+#[test]
+fn bt_meta_info() {
+    test_harness(|| unsafe {
+        library_init();
+        let session = get_session();
+
+        let mut gid = A2Gid::default();
+        assert_eq!(
+            add_torrent(session, &mut gid, b"./tests/test.torrent", &vec![], -1),
+            0
+        );
+
+        let handle = get_download_handle(session, gid);
+        assert!(!handle.is_null());
+
+        let meta_info = handle.bt_meta_info();
+        assert_eq!(meta_info.mode(), BtFileMode::BT_FILE_MODE_MULTI);
+        assert_eq!(meta_info.name().to_string_lossy(), "aria2-test");
+        assert_eq!(meta_info.comment().to_string_lossy(), "REDNOAH.COM RULES");
+        assert_eq!(meta_info.creation_date(), 1123456789);
+        assert_eq!(meta_info.num_announce_tiers(), 3);
+        assert_eq!(
+            meta_info.announce_tier(1).get(0).unwrap().to_string_lossy(),
+            "http://tracker2"
+        );
+
+        delete_download_handle(handle);
+
+        shutdown(session, true);
+        session_final(session);
+        library_deinit();
+    })
+}
+
+#[test]
+fn event_callback_closure() {
+    test_harness(|| unsafe {
+        library_init();
+
+        let events = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let callback_events = events.clone();
+        let session = session_new(
+            &vec![KeyVal {
+                key: "no-conf".into(),
+                val: "true".into(),
+            }],
+            &SessionConfigFfi {
+                keep_running: false,
+                use_signal_handler: false,
+            },
+            EventCallback::new(move |_, event, gid| {
+                callback_events.borrow_mut().push((event, gid));
+                0
+            }),
+        );
+        assert!(session.is_valid());
+
+        let mut gid = A2Gid::default();
+        assert_eq!(
+            add_uri(
+                session,
+                &mut gid,
+                &vec!["http://localhost/1".into()],
+                &vec![],
+                -1
+            ),
+            0
+        );
+
+        assert_eq!(tick(session), 1);
+        assert!(events
+            .borrow()
+            .iter()
+            .any(|&(event, g)| event == DownloadEvent::EVENT_ON_DOWNLOAD_START && g == gid));
+
+        shutdown(session, true);
+        session_final(session);
+        library_deinit();
+    })
+}
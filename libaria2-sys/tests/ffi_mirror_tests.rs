@@ -58,9 +58,8 @@ pub unsafe fn get_session() -> SessionHandle {
         &SessionConfigFfi {
             keep_running: false,
             use_signal_handler: false,
-            user_data: 0,
         },
-        |_, _, _, _| 0,
+        EventCallback::new(|_, _, _| 0),
     )
 }
 
@@ -107,13 +106,71 @@ fn test_add_uri() {
     });
 }
 
+#[test]
+fn test_add_uri_mirrors() {
+    test_harness(|| unsafe {
+        library_init();
+        let session = get_session();
+
+        let mut gid = A2Gid::default();
+        let uris: Vec<String> = vec![
+            "http://localhost/1".into(),
+            "http://127.0.0.1/1".into(),
+            "http://[::1]/1".into(),
+        ];
+        assert_eq!(add_uri(session, &mut gid, &uris, &vec![], -1), 0);
+        assert!(!is_gid_null(gid));
+
+        {
+            let handle = get_download_handle(session, gid);
+            assert!(!handle.is_null());
+            assert_eq!(handle.num_files(), 1);
+            let file = handle.get_file(1);
+            let file_uris = file
+                .uris()
+                .iter()
+                .map(|uri| uri.uri().to_string_lossy().into_owned())
+                .collect::<Vec<_>>();
+            assert_eq!(file_uris, uris);
+        }
+
+        session_final(session);
+        library_deinit();
+    });
+}
+
+#[test]
+fn test_add_uri_no_empty_mirror() {
+    test_harness(|| unsafe {
+        library_init();
+        let session = get_session();
+
+        let mut gid = A2Gid::default();
+        let uris = vec!["http://localhost/1".into(), "http://localhost/2".into()];
+        assert_eq!(add_uri(session, &mut gid, &uris, &vec![], -1), 0);
+
+        {
+            let handle = get_download_handle(session, gid);
+            assert!(!handle.is_null());
+            let files = handle.files();
+            assert_eq!(files.len(), 1);
+            let file = files.get(0).unwrap();
+            assert_eq!(file.uris().len(), uris.len());
+            assert!(file.uris().iter().all(|uri| !uri.uri().is_empty()));
+        }
+
+        session_final(session);
+        library_deinit();
+    });
+}
+
 #[test]
 fn test_add_metalink() {
     test_harness(|| unsafe {
         library_init();
         let session = get_session();
 
-        let metalink_path = "./tests/metalink4.xml";
+        let metalink_path = b"./tests/metalink4.xml";
         let mut gids = vec![];
 
         assert_eq!(
@@ -147,7 +204,7 @@ fn test_add_torrent() {
         library_init();
         let session = get_session();
 
-        let torrent_path = "./tests/test.torrent";
+        let torrent_path = b"./tests/test.torrent";
         let mut gid = A2Gid::default();
 
         assert_eq!(add_torrent(session, &mut gid, torrent_path, &vec![], -1), 0);
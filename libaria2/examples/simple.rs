@@ -2,9 +2,11 @@ use libaria2::{prelude::*, session::RunResult};
 
 fn main() {
     let mut aria = Aria2Context::new().unwrap();
-    let mut session = aria.new_session(true, &[]);
+    let mut session = aria.new_session(true, &Options::new()).unwrap();
 
-    let gid = session.add_uri("https://via.placeholder.com/150").unwrap();
+    let gid = session
+        .add_uri(&DownloadRequest::new("https://via.placeholder.com/150"))
+        .unwrap();
 
     let (res, ctx) = session.poll(true).unwrap();
     assert_eq!(res, RunResult::Continue);
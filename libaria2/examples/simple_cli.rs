@@ -12,9 +12,11 @@ fn main() {
     let dir = std::env::args().nth(2).unwrap_or(".".to_owned());
 
     let mut aria = Aria2Context::new().unwrap();
-    let mut session = aria.new_session(false, &[("dir", &dir)]);
+    let mut session = aria
+        .new_session(false, &Options::new().dir(&dir).unwrap())
+        .unwrap();
 
-    let gid = session.add_uri(&url).unwrap();
+    let gid = session.add_uri(&DownloadRequest::new(&url)).unwrap();
 
     println!("Downloading {} until completion ...", url);
     let (res, ctx) = session.poll(false).unwrap();
@@ -23,5 +25,5 @@ fn main() {
     let handle = ctx.acquire_handle(gid).unwrap();
     assert_eq!(handle.status(), DownloadStatus::Complete);
 
-    println!("Done ! Saved in: {}", handle.dir());
-}
\ No newline at end of file
+    println!("Done ! Saved in: {}", handle.dir().display());
+}
@@ -1,43 +1,120 @@
 use std::path::Path;
 
 use crate::{
+    download_handle::DownloadStatus,
     errors::{AriaError, Result},
+    events::DownloadEvent,
+    gid::Gid,
+    path::path_to_bytes,
+    request::{DownloadOptions, DownloadRequest},
     session::Session,
 };
 use libaria2_sys::{ffi, A2Gid};
 
+/// How the offset of a [`Position`] is interpreted.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum OffsetMode {
+    /// From the beginning of the queue.
+    Set,
+    /// From the current position of the download.
+    Cur,
+    /// From the end of the queue.
+    End,
+}
+
+impl From<OffsetMode> for ffi::OffsetMode {
+    fn from(mode: OffsetMode) -> Self {
+        match mode {
+            OffsetMode::Set => ffi::OffsetMode::OFFSET_MODE_SET,
+            OffsetMode::Cur => ffi::OffsetMode::OFFSET_MODE_CUR,
+            OffsetMode::End => ffi::OffsetMode::OFFSET_MODE_END,
+        }
+    }
+}
+
+/// Destination of a download in the waiting queue.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Position {
+    Start(i32),
+    Current(i32),
+    End(i32),
+}
+
+impl Position {
+    pub fn offset(&self) -> i32 {
+        match *self {
+            Position::Start(offset) | Position::Current(offset) | Position::End(offset) => offset,
+        }
+    }
+
+    pub fn mode(&self) -> OffsetMode {
+        match self {
+            Position::Start(_) => OffsetMode::Set,
+            Position::Current(_) => OffsetMode::Cur,
+            Position::End(_) => OffsetMode::End,
+        }
+    }
+}
+
 impl<U> Session<'_, U> {
-    pub fn add_uri(&mut self, uri: &str) -> Result<A2Gid> {
+    pub fn add_uri(&mut self, request: &DownloadRequest) -> Result<Gid> {
         let mut gid = A2Gid::default();
-        let res =
-            unsafe { ffi::add_uri(self.handle, &mut gid, &vec![uri.to_string()], &vec![], -1) };
+        let options = request.download_options();
+        options.as_ref().validate_for_download()?;
+        let res = unsafe {
+            ffi::add_uri(
+                self.handle,
+                &mut gid,
+                &request.uris().to_vec(),
+                &options.ffi_options(),
+                options.ffi_position(),
+            )
+        };
 
         if res == 0 {
-            Ok(gid)
+            Ok(self.added(gid))
         } else {
             Err(AriaError::AddError(res))
         }
     }
 
-    pub fn add_metalink(&mut self, file: &Path) -> Result<Vec<A2Gid>> {
+    pub fn add_metalink(&mut self, file: &Path, options: &DownloadOptions) -> Result<Vec<Gid>> {
+        options.as_ref().validate_for_download()?;
         let mut gids = Vec::new();
-        let filename = file.to_string_lossy();
-        let res = unsafe { ffi::add_metalink(self.handle, &mut gids, &filename, &vec![], -1) };
+        let filename = path_to_bytes(file)?;
+        let res = unsafe {
+            ffi::add_metalink(
+                self.handle,
+                &mut gids,
+                filename,
+                &options.ffi_options(),
+                options.ffi_position(),
+            )
+        };
 
         if res == 0 {
-            Ok(gids)
+            Ok(gids.into_iter().map(|gid| self.added(gid)).collect())
         } else {
             Err(AriaError::AddError(res))
         }
     }
 
-    pub fn add_torrent(&mut self, file: &Path) -> Result<A2Gid> {
+    pub fn add_torrent(&mut self, file: &Path, options: &DownloadOptions) -> Result<Gid> {
+        options.as_ref().validate_for_download()?;
         let mut gid = A2Gid::default();
-        let filename = file.to_string_lossy();
-        let res = unsafe { ffi::add_torrent(self.handle, &mut gid, &filename, &vec![], -1) };
+        let filename = path_to_bytes(file)?;
+        let res = unsafe {
+            ffi::add_torrent(
+                self.handle,
+                &mut gid,
+                filename,
+                &options.ffi_options(),
+                options.ffi_position(),
+            )
+        };
 
         if res == 0 {
-            Ok(gid)
+            Ok(self.added(gid))
         } else {
             Err(AriaError::AddError(res))
         }
@@ -46,25 +123,103 @@ impl<U> Session<'_, U> {
     pub fn add_torrent_with_webseed_uris(
         &mut self,
         file: &Path,
-        webseeds: &Vec<String>,
-    ) -> Result<A2Gid> {
+        webseeds: &[String],
+        options: &DownloadOptions,
+    ) -> Result<Gid> {
+        options.as_ref().validate_for_download()?;
         let mut gid = A2Gid::default();
-        let filename = file.to_string_lossy();
+        let filename = path_to_bytes(file)?;
         let res = unsafe {
             ffi::add_torrent_with_webseed_uris(
                 self.handle,
                 &mut gid,
-                &filename,
-                webseeds,
-                &vec![],
-                -1,
+                filename,
+                &webseeds.to_vec(),
+                &options.ffi_options(),
+                options.ffi_position(),
             )
         };
 
         if res == 0 {
-            Ok(gid)
+            Ok(self.added(gid))
         } else {
             Err(AriaError::AddError(res))
         }
     }
+
+    /// Pause an active or waiting download, with `force` it won't wait for the connections to
+    /// close gracefully.
+    pub fn pause(&mut self, gid: Gid, force: bool) -> Result<()> {
+        self.control_download("pause", gid, |handle| unsafe {
+            ffi::pause_download(handle, gid.raw(), force)
+        })
+        .map(|_| ())
+    }
+
+    /// Resume a paused download.
+    pub fn unpause(&mut self, gid: Gid) -> Result<()> {
+        self.control_download("unpause", gid, |handle| unsafe {
+            ffi::unpause_download(handle, gid.raw())
+        })
+        .map(|_| ())
+    }
+
+    /// Remove an active, waiting or paused download, with `force` it won't wait for the
+    /// connections to close gracefully.
+    pub fn remove(&mut self, gid: Gid, force: bool) -> Result<()> {
+        self.control_download("remove", gid, |handle| unsafe {
+            ffi::remove_download(handle, gid.raw(), force)
+        })?;
+        self.emit(DownloadEvent::Removed(gid));
+        Ok(())
+    }
+
+    /// Move a download in the waiting queue and return its new position.
+    pub fn move_to(&mut self, gid: Gid, position: Position) -> Result<usize> {
+        self.control_download("move", gid, |handle| unsafe {
+            ffi::change_position(handle, gid.raw(), position.offset(), position.mode().into())
+        })
+        .map(|pos| pos as usize)
+    }
+
+    fn added(&mut self, gid: A2Gid) -> Gid {
+        let gid = Gid::from(gid);
+        self.emit(DownloadEvent::Added(gid));
+        gid
+    }
+
+    /// Run a download control function and turn its error into something meaningful,
+    /// aria2 only returns -1 whether the GID is unknown or the download is in the wrong state.
+    fn control_download<F>(&mut self, action: &'static str, gid: Gid, f: F) -> Result<i32>
+    where
+        F: FnOnce(ffi::SessionHandle) -> i32,
+    {
+        let status = self
+            .download_status(gid)
+            .ok_or(AriaError::UnknownGid(gid))?;
+
+        let res = f(self.handle);
+        if res < 0 {
+            // The state may have changed during the call
+            let status = self.download_status(gid).unwrap_or(status);
+            Err(AriaError::InvalidDownloadState {
+                action,
+                gid,
+                status,
+            })
+        } else {
+            Ok(res)
+        }
+    }
+
+    pub(crate) fn download_status(&self, gid: Gid) -> Option<DownloadStatus> {
+        if gid.is_null() {
+            return None;
+        }
+
+        let handle = unsafe { ffi::get_download_handle(self.handle, gid.raw()) };
+        handle
+            .as_ref()
+            .map(|handle| unsafe { handle.status() }.into())
+    }
 }
@@ -0,0 +1,296 @@
+//! Session running on its own thread, controlled from async code.
+
+use futures::{channel::oneshot, StreamExt};
+use log::error;
+use std::{
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    sync::{
+        mpsc::{self, Receiver, Sender, TryRecvError},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crate::{
+    download_info::DownloadInfo,
+    errors::{AriaError, Result},
+    gid::Gid,
+    options::Options,
+    request::{DownloadOptions, DownloadRequest},
+    session::{Aria2Context, RunResult, Session, SessionBuilder},
+    stats::{DownloadStats, GlobalStat},
+    subscription::EventSubscription,
+    wait::DownloadOutcome,
+};
+
+const WAIT_EVENT_BUFFER: usize = 64;
+
+type Call = Box<dyn for<'a> FnOnce(&mut Session<'a, ()>) + Send>;
+
+enum Command {
+    Call(Call),
+    Shutdown(bool),
+}
+
+/// Owns a session running on a dedicated thread.
+///
+/// The session is controlled through [`AsyncSessionHandle`]s, commands are served between two
+/// rounds of aria2's event loop. It keeps running when there is nothing to download, until it is
+/// shut down or dropped, which shuts it down forcefully.
+pub struct AsyncSession {
+    handle: AsyncSessionHandle,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl AsyncSession {
+    /// Start a session with `options` on a new thread, which owns `ctx` until the session ends.
+    pub fn spawn(mut ctx: Aria2Context, options: Options) -> Result<Self> {
+        let (sender, receiver) = mpsc::channel();
+        let (ready_sender, ready_receiver) = mpsc::channel();
+
+        let thread = thread::Builder::new()
+            .name("aria2".into())
+            .spawn(move || {
+                // Downloads can be added at any time, so aria2 must not stop once the current
+                // ones are finished.
+                let session = SessionBuilder::new()
+                    .keep_running(true)
+                    .options(&options)
+                    .build(&mut ctx);
+                let mut session = match session {
+                    Ok(session) => session,
+                    Err(e) => {
+                        let _ = ready_sender.send(Err(e));
+                        return;
+                    }
+                };
+                let _ = ready_sender.send(Ok(()));
+                run_commands(&mut session, receiver);
+            })
+            .expect("Failed to spawn the aria2 thread");
+
+        ready_receiver
+            .recv()
+            .map_err(|_| AriaError::SessionClosed)??;
+
+        Ok(Self {
+            handle: AsyncSessionHandle {
+                sender: Arc::new(Mutex::new(sender)),
+            },
+            thread: Some(thread),
+        })
+    }
+
+    pub fn handle(&self) -> AsyncSessionHandle {
+        self.handle.clone()
+    }
+
+    /// Stop the session and wait for it to end, with `force` it won't wait for the connections
+    /// to close gracefully.
+    pub fn shutdown(mut self, force: bool) {
+        self.stop(force);
+    }
+
+    fn stop(&mut self, force: bool) {
+        if let Some(thread) = self.thread.take() {
+            let _ = self.handle.send(Command::Shutdown(force));
+            if thread.join().is_err() {
+                error!("The aria2 thread panicked");
+            }
+        }
+    }
+}
+
+impl Drop for AsyncSession {
+    fn drop(&mut self) {
+        self.stop(true);
+    }
+}
+
+/// Cheap to clone handle to an [`AsyncSession`].
+///
+/// Every method resolves to [`AriaError::SessionClosed`] once the session is shut down, and to
+/// [`AriaError::CommandPanicked`] if the call panicked on the session thread.
+#[derive(Clone)]
+pub struct AsyncSessionHandle {
+    sender: Arc<Mutex<Sender<Command>>>,
+}
+
+impl AsyncSessionHandle {
+    pub async fn add_uri(&self, request: DownloadRequest) -> Result<Gid> {
+        self.call(move |session| session.add_uri(&request)).await
+    }
+
+    pub async fn add_metalink(&self, file: PathBuf, options: DownloadOptions) -> Result<Vec<Gid>> {
+        self.call(move |session| session.add_metalink(&file, &options))
+            .await
+    }
+
+    pub async fn add_torrent(&self, file: PathBuf, options: DownloadOptions) -> Result<Gid> {
+        self.call(move |session| session.add_torrent(&file, &options))
+            .await
+    }
+
+    pub async fn pause(&self, gid: Gid, force: bool) -> Result<()> {
+        self.call(move |session| session.pause(gid, force)).await
+    }
+
+    pub async fn unpause(&self, gid: Gid) -> Result<()> {
+        self.call(move |session| session.unpause(gid)).await
+    }
+
+    pub async fn remove(&self, gid: Gid, force: bool) -> Result<()> {
+        self.call(move |session| session.remove(gid, force)).await
+    }
+
+    pub async fn change_option(&self, gid: Gid, options: Options) -> Result<()> {
+        self.call(move |session| session.change_option(gid, &options))
+            .await
+    }
+
+    pub async fn global_stat(&self) -> Result<GlobalStat> {
+        self.call(|session| Ok(session.global_stat())).await
+    }
+
+    pub async fn stats_for(&self, gids: Vec<Gid>) -> Result<Vec<DownloadStats>> {
+        self.call(move |session| Ok(session.stats_for(&gids))).await
+    }
+
+    /// Same as [`Session::subscribe`], the subscription can be used as a `Stream`.
+    pub async fn subscribe(&self, capacity: usize) -> Result<EventSubscription> {
+        self.call(move |session| Ok(session.subscribe(capacity)))
+            .await
+    }
+
+    /// Same as [`Session::set_progress_interval`].
+    pub async fn set_progress_interval(&self, interval: Option<Duration>) -> Result<()> {
+        self.call(move |session| {
+            session.set_progress_interval(interval);
+            Ok(())
+        })
+        .await
+    }
+
+    /// Same as [`Session::set_stall_timeout`].
+    pub async fn set_stall_timeout(&self, timeout: Option<Duration>) -> Result<()> {
+        self.call(move |session| {
+            session.set_stall_timeout(timeout);
+            Ok(())
+        })
+        .await
+    }
+
+    /// Snapshot of the download `gid`, `None` if there is no such download.
+    pub async fn snapshot(&self, gid: Gid) -> Result<Option<DownloadInfo>> {
+        self.call(move |session| Ok(session.snapshot(gid))).await
+    }
+
+    /// Wait until `gid` and the downloads following it are finished, see
+    /// [`Session::run_until_finished`].
+    pub async fn wait_for(&self, gid: Gid) -> Result<DownloadOutcome> {
+        let mut events = self.subscribe(WAIT_EVENT_BUFFER).await?;
+        let mut pending = vec![gid];
+        loop {
+            let (settled, outcome) = self
+                .call(move |session| {
+                    let outcome = session.settle(&mut pending)?;
+                    Ok((pending, outcome))
+                })
+                .await?;
+            if let Some(outcome) = outcome {
+                return Ok(outcome);
+            }
+            pending = settled;
+
+            // Check again on the next event about a pending download, or if some were missed.
+            let missed = events.missed();
+            loop {
+                match events.next().await {
+                    Some(event) if pending.contains(&event.gid()) => break,
+                    Some(_) if events.missed() != missed => break,
+                    Some(_) => {}
+                    None => return Err(AriaError::SessionClosed),
+                }
+            }
+        }
+    }
+
+    /// Run `f` on the session thread and return its result.
+    async fn call<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Session<'_, ()>) -> Result<T> + Send + 'static,
+    {
+        let (sender, receiver) = oneshot::channel();
+        self.send(Command::Call(Box::new(move |session| {
+            // A panicking call must not take the session thread down with it.
+            let res = panic::catch_unwind(AssertUnwindSafe(|| f(session)))
+                .unwrap_or(Err(AriaError::CommandPanicked));
+            let _ = sender.send(res);
+        })))?;
+
+        receiver.await.map_err(|_| AriaError::SessionClosed)?
+    }
+
+    fn send(&self, command: Command) -> Result<()> {
+        self.sender
+            .lock()
+            .unwrap()
+            .send(command)
+            .map_err(|_| AriaError::SessionClosed)
+    }
+}
+
+/// Serve commands until the session is shut down.
+fn run_commands(session: &mut Session<'_, ()>, receiver: Receiver<Command>) {
+    let mut idle = true;
+    loop {
+        // There is no point in running aria2 when there is nothing to download, so wait for a
+        // command instead.
+        let first = if idle {
+            match receiver.recv() {
+                Ok(command) => Some(command),
+                Err(_) => break,
+            }
+        } else {
+            match receiver.try_recv() {
+                Ok(command) => Some(command),
+                Err(TryRecvError::Empty) => None,
+                Err(TryRecvError::Disconnected) => break,
+            }
+        };
+
+        for command in first.into_iter().chain(receiver.try_iter()) {
+            match command {
+                Command::Call(call) => call(session),
+                Command::Shutdown(force) => {
+                    session.shutdown(force);
+                    while let Ok((RunResult::Event(_) | RunResult::Continue, _)) =
+                        session.poll(true)
+                    {}
+                    return;
+                }
+            }
+        }
+
+        idle = match session.poll(true) {
+            Ok((RunResult::Event(_), _)) => false,
+            Ok((RunResult::Continue, _)) => is_idle(session),
+            Ok((RunResult::Done, _)) => true,
+            Err(e) => {
+                error!("{}", e);
+                true
+            }
+        };
+    }
+}
+
+/// Whether there is nothing to do until the next command, aria2 keeps running but it would only
+/// wake up to tick its timers.
+fn is_idle(session: &mut Session<'_, ()>) -> bool {
+    session.drain_events();
+    let stat = session.global_stat();
+    session.is_event_queue_empty() && stat.num_active == 0 && stat.num_waiting == 0
+}
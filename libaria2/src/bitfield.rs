@@ -0,0 +1,104 @@
+use std::ops::Range;
+
+/// Which pieces of a download are complete, decoded from aria2's hex bitfield.
+///
+/// The high bit of the first byte is piece 0.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PieceBitfield {
+    bits: Vec<u8>,
+    num_pieces: u32,
+    piece_len: u64,
+    total_len: u64,
+}
+
+impl PieceBitfield {
+    /// Decode a bitfield as returned by aria2, `None` if it isn't valid hex or is too short to
+    /// hold `num_pieces` bits.
+    pub fn from_hex(hex: &str, num_pieces: u32, piece_len: u64, total_len: u64) -> Option<Self> {
+        if hex.len() % 2 != 0 {
+            return None;
+        }
+
+        let bits = hex
+            .as_bytes()
+            .chunks(2)
+            .map(|pair| {
+                let digit = |c: u8| (c as char).to_digit(16);
+                Some((digit(pair[0])? << 4 | digit(pair[1])?) as u8)
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        if bits.len() * 8 < num_pieces as usize {
+            return None;
+        }
+
+        Some(Self {
+            bits,
+            num_pieces,
+            piece_len,
+            total_len,
+        })
+    }
+
+    pub fn num_pieces(&self) -> u32 {
+        self.num_pieces
+    }
+
+    pub fn piece_len(&self) -> u64 {
+        self.piece_len
+    }
+
+    pub fn total_len(&self) -> u64 {
+        self.total_len
+    }
+
+    /// Whether piece `index` is complete, `false` if it is out of range.
+    pub fn has_piece(&self, index: u32) -> bool {
+        index < self.num_pieces && self.bits[index as usize / 8] & (0x80 >> (index % 8)) != 0
+    }
+
+    pub fn completed_count(&self) -> u32 {
+        (0..self.num_pieces).filter(|&i| self.has_piece(i)).count() as u32
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.first_missing().is_none()
+    }
+
+    pub fn first_missing(&self) -> Option<u32> {
+        (0..self.num_pieces).find(|&i| !self.has_piece(i))
+    }
+
+    /// Completion state of every piece, in order.
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.num_pieces).map(move |i| self.has_piece(i))
+    }
+
+    /// Byte range covered by piece `index`, the last piece is usually shorter.
+    pub fn piece_range(&self, index: u32) -> Range<u64> {
+        let start = (index as u64 * self.piece_len).min(self.total_len);
+        let end = (start + self.piece_len).min(self.total_len);
+        start..end
+    }
+
+    /// Contiguous byte ranges of completed pieces.
+    pub fn completed_ranges(&self) -> Vec<Range<u64>> {
+        let mut ranges: Vec<Range<u64>> = Vec::new();
+        for index in (0..self.num_pieces).filter(|&i| self.has_piece(i)) {
+            let range = self.piece_range(index);
+            match ranges.last_mut() {
+                Some(last) if last.end == range.start => last.end = range.end,
+                _ => ranges.push(range),
+            }
+        }
+        ranges
+    }
+
+    /// Number of bytes available from the start of the download without a gap.
+    pub fn completed_prefix_len(&self) -> u64 {
+        match self.first_missing() {
+            Some(index) => self.piece_range(index).start,
+            None => self.total_len,
+        }
+    }
+}
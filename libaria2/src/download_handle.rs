@@ -1,6 +1,20 @@
-use libaria2_sys::{cxx, ffi, A2Gid};
+use libaria2_sys::{cxx, ffi};
+use std::{
+    borrow::Cow,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
 
-use crate::session::PollContext;
+use crate::{
+    bitfield::PieceBitfield,
+    error_code::Aria2ErrorCode,
+    gid::Gid,
+    info_hash::InfoHash,
+    options::Options,
+    path::{os_string_from_bytes, path_from_bytes},
+    session::PollContext,
+};
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum DownloadStatus {
@@ -27,11 +41,16 @@ impl From<ffi::DownloadStatus> for DownloadStatus {
 }
 
 pub struct DownloadHandle<'a> {
+    gid: Gid,
     handle: cxx::UniquePtr<ffi::DownloadHandleWrapper>,
     _phantom: std::marker::PhantomData<&'a ()>,
 }
 
 impl DownloadHandle<'_> {
+    pub fn gid(&self) -> Gid {
+        self.gid
+    }
+
     pub fn status(&self) -> DownloadStatus {
         unsafe { self.handle.status().into() }
     }
@@ -48,10 +67,21 @@ impl DownloadHandle<'_> {
         unsafe { self.handle.upload_len() }
     }
 
+    /// Hex-encoded bitfield of the completed pieces, see [`pieces`](Self::pieces) to decode it.
     pub fn bitfield(&self) -> String {
         unsafe { self.handle.bitfield() }
     }
 
+    /// Completed pieces of the download, `None` if aria2 gave an invalid bitfield.
+    pub fn pieces(&self) -> Option<PieceBitfield> {
+        PieceBitfield::from_hex(
+            &self.bitfield(),
+            self.num_pieces(),
+            self.piece_len() as u64,
+            self.total_len() as u64,
+        )
+    }
+
     pub fn download_speed(&self) -> u32 {
         unsafe { self.handle.download_speed() }
     }
@@ -60,8 +90,16 @@ impl DownloadHandle<'_> {
         unsafe { self.handle.upload_speed() }
     }
 
-    pub fn info_hash(&self) -> &str {
-        unsafe { self.handle.info_hash() }.to_str().unwrap()
+    /// Info hash of the torrent, `None` if the download isn't a BitTorrent download.
+    pub fn info_hash(&self) -> Option<InfoHash> {
+        InfoHash::from_bytes(unsafe { self.handle.info_hash() }.as_bytes())
+    }
+
+    /// Whether this is a completed torrent still uploading to its peers.
+    pub fn is_seeding(&self) -> bool {
+        self.info_hash().is_some()
+            && self.total_len() > 0
+            && self.completed_len() == self.total_len()
     }
 
     pub fn piece_len(&self) -> usize {
@@ -76,78 +114,262 @@ impl DownloadHandle<'_> {
         unsafe { self.handle.connections() }
     }
 
-    pub fn error_code(&self) -> i32 {
-        unsafe { self.handle.error_code() }
+    /// Why the download failed, [`Aria2ErrorCode::Finished`] if it didn't.
+    pub fn error_code(&self) -> Aria2ErrorCode {
+        unsafe { self.handle.error_code() }.into()
     }
 
-    pub fn followed_by(&self) -> &[A2Gid] {
-        unsafe { self.handle.followed_by().as_slice() }
+    pub fn followed_by(&self) -> Vec<Gid> {
+        let followed_by = unsafe { self.handle.followed_by() };
+        followed_by.iter().copied().map(Gid::from).collect()
     }
 
-    pub fn following(&self) -> Option<A2Gid> {
-        let following = unsafe { self.handle.following() };
-        if ffi::is_gid_null(following) {
-            None
-        } else {
-            Some(following)
-        }
+    pub fn following(&self) -> Option<Gid> {
+        Some(Gid::from(unsafe { self.handle.following() })).filter(|gid| !gid.is_null())
     }
 
-    pub fn belongs_to(&self) -> Option<A2Gid> {
-        let belongs = unsafe { self.handle.belongs_to() };
-        if ffi::is_gid_null(belongs) {
-            None
-        } else {
-            Some(belongs)
-        }
+    pub fn belongs_to(&self) -> Option<Gid> {
+        Some(Gid::from(unsafe { self.handle.belongs_to() })).filter(|gid| !gid.is_null())
     }
 
-    pub fn dir(&self) -> &str {
-        unsafe { self.handle.dir().to_str().unwrap() }
+    pub fn dir(&self) -> PathBuf {
+        path_from_bytes(unsafe { self.handle.dir() }.as_bytes())
     }
 
     pub fn files(&self) -> Vec<FileData> {
-        (0..self.num_files())
-            .into_iter()
-            .map(|i| self.get_file(i))
-            .collect()
+        let files = unsafe { self.handle.files() };
+        files.iter().map(FileData::from).collect()
     }
 
     pub fn num_files(&self) -> u32 {
         unsafe { self.handle.num_files() }
     }
 
-    pub fn get_file(&self, index: u32) -> FileData {
+    /// Get the file at `index`, aria2 indexes files starting from 1.
+    pub fn get_file(&self, index: u32) -> Option<FileData> {
+        if index == 0 || index > self.num_files() {
+            return None;
+        }
+
         let file = unsafe { self.handle.get_file(index) };
-        FileData { inner: file }
+        file.as_ref().map(FileData::from)
+    }
+
+    /// BitTorrent metainfo of the download, `None` if it isn't a torrent or if it is a magnet
+    /// link whose metadata wasn't received yet.
+    pub fn bt_meta_info(&self) -> Option<BtMetaInfo> {
+        let meta_info = unsafe { self.handle.bt_meta_info() };
+        BtMetaInfo::from_ffi(meta_info.as_ref()?)
     }
 
-    /*pub fn bt_meta_info(&self) -> ffi::BtMetaInfo {
-        unsafe { self.handle.bt_meta_info() }
-    }*/
+    /// Value of the option `name` for this download, invalid UTF-8 is replaced.
+    ///
+    /// Use [`get_option_os`](Self::get_option_os) for options holding paths, like `dir` or `out`.
+    pub fn get_option(&self, name: &str) -> Cow<'_, str> {
+        unsafe { self.handle.get_option(name) }.to_string_lossy()
+    }
 
-    pub fn get_option(&self, name: &str) -> &str {
-        unsafe { self.handle.get_option(name) }.to_str().unwrap()
+    /// Value of the option `name` for this download, as the raw bytes given by aria2.
+    pub fn get_option_os(&self, name: &str) -> OsString {
+        os_string_from_bytes(unsafe { self.handle.get_option(name) }.as_bytes())
     }
 
-    pub fn options(&self) -> Vec<ffi::KeyVal> {
-        unsafe { self.handle.options() }
+    pub fn options(&self) -> Options {
+        unsafe { self.handle.options() }.into()
     }
 }
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum UriStatus {
+    Used,
+    Waiting,
+}
+
+impl From<ffi::UriStatus> for UriStatus {
+    fn from(s: ffi::UriStatus) -> Self {
+        match s {
+            ffi::UriStatus::URI_USED => UriStatus::Used,
+            ffi::UriStatus::URI_WAITING => UriStatus::Waiting,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UriData {
+    uri: String,
+    status: UriStatus,
+}
+
+impl UriData {
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    pub fn status(&self) -> UriStatus {
+        self.status
+    }
+}
+
+impl From<&ffi::UriDataWrapper> for UriData {
+    fn from(data: &ffi::UriDataWrapper) -> Self {
+        unsafe {
+            Self {
+                uri: data.uri().to_string_lossy().into_owned(),
+                status: data.status().into(),
+            }
+        }
+    }
+}
+
+/// Copy of the state of a file of a download at the time it was requested.
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct FileData {
-    inner: cxx::UniquePtr<ffi::FileDataWrapper>,
+    index: u32,
+    path: PathBuf,
+    len: u64,
+    completed_len: u64,
+    selected: bool,
+    uris: Vec<UriData>,
+}
+
+impl FileData {
+    /// Index of the file in the download, starting from 1.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn completed_len(&self) -> u64 {
+        self.completed_len
+    }
+
+    /// Whether the whole file was downloaded, a file whose length isn't known yet is reported
+    /// with a length of 0 and isn't completed.
+    pub fn is_completed(&self) -> bool {
+        self.len > 0 && self.completed_len == self.len
+    }
+
+    /// Whether this file is selected for download with `--select-file`.
+    pub fn selected(&self) -> bool {
+        self.selected
+    }
+
+    pub fn uris(&self) -> &[UriData] {
+        &self.uris
+    }
+}
+
+impl From<&ffi::FileDataWrapper> for FileData {
+    fn from(file: &ffi::FileDataWrapper) -> Self {
+        unsafe {
+            Self {
+                index: file.index(),
+                path: path_from_bytes(file.path().as_bytes()),
+                len: file.len(),
+                completed_len: file.completed_len(),
+                selected: file.selected(),
+                uris: file.uris().iter().map(UriData::from).collect(),
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BtFileMode {
+    Single,
+    Multi,
+}
+
+/// Information retrieved from the .torrent file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BtMetaInfo {
+    announce_list: Vec<Vec<String>>,
+    comment: String,
+    creation_date: Option<SystemTime>,
+    mode: BtFileMode,
+    name: String,
+}
+
+impl BtMetaInfo {
+    /// Announce URIs, grouped by tier.
+    pub fn announce_list(&self) -> &[Vec<String>] {
+        &self.announce_list
+    }
+
+    pub fn comment(&self) -> &str {
+        &self.comment
+    }
+
+    pub fn creation_date(&self) -> Option<SystemTime> {
+        self.creation_date
+    }
+
+    pub fn mode(&self) -> BtFileMode {
+        self.mode
+    }
+
+    /// Name of the torrent.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn from_ffi(meta_info: &ffi::BtMetaInfoDataWrapper) -> Option<Self> {
+        unsafe {
+            let mode = match meta_info.mode() {
+                ffi::BtFileMode::BT_FILE_MODE_NONE => return None,
+                ffi::BtFileMode::BT_FILE_MODE_SINGLE => BtFileMode::Single,
+                ffi::BtFileMode::BT_FILE_MODE_MULTI => BtFileMode::Multi,
+                _ => unreachable!(),
+            };
+
+            let announce_list = (0..meta_info.num_announce_tiers())
+                .map(|i| {
+                    meta_info
+                        .announce_tier(i)
+                        .iter()
+                        .map(|uri| uri.to_string_lossy().into_owned())
+                        .collect()
+                })
+                .collect();
+
+            let creation_date = match meta_info.creation_date() {
+                secs if secs > 0 => Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs as u64)),
+                _ => None,
+            };
+
+            Some(Self {
+                announce_list,
+                comment: meta_info.comment().to_string_lossy().into_owned(),
+                creation_date,
+                mode,
+                name: meta_info.name().to_string_lossy().into_owned(),
+            })
+        }
+    }
 }
 
 impl PollContext<'_> {
-    pub fn acquire_handle(&self, gid: A2Gid) -> Option<DownloadHandle> {
-        debug_assert!(!ffi::is_gid_null(gid));
-        let handle = unsafe { ffi::get_download_handle(self.handle, gid) };
+    pub fn acquire_handle(&self, gid: Gid) -> Option<DownloadHandle> {
+        debug_assert!(!gid.is_null());
+        let handle = unsafe { ffi::get_download_handle(self.handle, gid.raw()) };
 
         if handle.is_null() {
             None
         } else {
             Some(DownloadHandle {
+                gid,
                 handle,
                 _phantom: Default::default(),
             })
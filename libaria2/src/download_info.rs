@@ -0,0 +1,162 @@
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::{
+    bitfield::PieceBitfield,
+    download_handle::{BtMetaInfo, DownloadHandle, DownloadStatus, FileData},
+    error_code::Aria2ErrorCode,
+    gid::Gid,
+    info_hash::InfoHash,
+    options::Options,
+    session::{PollContext, Session},
+};
+
+/// Owned copy of the state of a download, it stays valid after the next poll of the session.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DownloadInfo {
+    gid: Gid,
+    captured_at: SystemTime,
+    status: DownloadStatus,
+    total_len: u64,
+    completed_len: u64,
+    upload_len: u64,
+    download_speed: u32,
+    upload_speed: u32,
+    connections: u32,
+    error_code: Aria2ErrorCode,
+    pieces: Option<PieceBitfield>,
+    info_hash: Option<InfoHash>,
+    followed_by: Vec<Gid>,
+    following: Option<Gid>,
+    belongs_to: Option<Gid>,
+    dir: PathBuf,
+    files: Vec<FileData>,
+    options: Options,
+    bt_meta_info: Option<BtMetaInfo>,
+}
+
+impl DownloadInfo {
+    pub fn gid(&self) -> Gid {
+        self.gid
+    }
+
+    /// When the snapshot was taken.
+    pub fn captured_at(&self) -> SystemTime {
+        self.captured_at
+    }
+
+    pub fn status(&self) -> DownloadStatus {
+        self.status
+    }
+
+    pub fn total_len(&self) -> u64 {
+        self.total_len
+    }
+
+    pub fn completed_len(&self) -> u64 {
+        self.completed_len
+    }
+
+    pub fn upload_len(&self) -> u64 {
+        self.upload_len
+    }
+
+    pub fn download_speed(&self) -> u32 {
+        self.download_speed
+    }
+
+    pub fn upload_speed(&self) -> u32 {
+        self.upload_speed
+    }
+
+    pub fn connections(&self) -> u32 {
+        self.connections
+    }
+
+    pub fn error_code(&self) -> Aria2ErrorCode {
+        self.error_code
+    }
+
+    pub fn pieces(&self) -> Option<&PieceBitfield> {
+        self.pieces.as_ref()
+    }
+
+    pub fn info_hash(&self) -> Option<InfoHash> {
+        self.info_hash
+    }
+
+    pub fn followed_by(&self) -> &[Gid] {
+        &self.followed_by
+    }
+
+    pub fn following(&self) -> Option<Gid> {
+        self.following
+    }
+
+    pub fn belongs_to(&self) -> Option<Gid> {
+        self.belongs_to
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn files(&self) -> &[FileData] {
+        &self.files
+    }
+
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    pub fn bt_meta_info(&self) -> Option<&BtMetaInfo> {
+        self.bt_meta_info.as_ref()
+    }
+
+    /// Fraction of the download that is completed, between 0 and 1.
+    pub fn progress(&self) -> f64 {
+        if self.total_len == 0 {
+            0.0
+        } else {
+            self.completed_len as f64 / self.total_len as f64
+        }
+    }
+}
+
+impl DownloadHandle<'_> {
+    /// Copy the whole state of the download so it can be kept around or sent to another thread.
+    pub fn snapshot(&self) -> DownloadInfo {
+        DownloadInfo {
+            gid: self.gid(),
+            captured_at: SystemTime::now(),
+            status: self.status(),
+            total_len: self.total_len() as u64,
+            completed_len: self.completed_len() as u64,
+            upload_len: self.upload_len() as u64,
+            download_speed: self.download_speed(),
+            upload_speed: self.upload_speed(),
+            connections: self.connections(),
+            error_code: self.error_code(),
+            pieces: self.pieces(),
+            info_hash: self.info_hash(),
+            followed_by: self.followed_by(),
+            following: self.following(),
+            belongs_to: self.belongs_to(),
+            dir: self.dir(),
+            files: self.files(),
+            options: self.options(),
+            bt_meta_info: self.bt_meta_info(),
+        }
+    }
+}
+
+impl<U> Session<'_, U> {
+    /// Snapshot of the download `gid`, `None` if there is no such download.
+    pub fn snapshot(&self, gid: Gid) -> Option<DownloadInfo> {
+        PollContext::new(self)
+            .acquire_handle(gid)
+            .map(|handle| handle.snapshot())
+    }
+}
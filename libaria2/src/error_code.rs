@@ -0,0 +1,199 @@
+use std::fmt::{Display, Formatter};
+
+/// Exit status of a download, as documented in the "Exit Status" section of the aria2 manual.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Aria2ErrorCode {
+    /// All downloads were successful.
+    Finished,
+    UnknownError,
+    TimeOut,
+    ResourceNotFound,
+    /// `max-file-not-found` was reached.
+    MaxFileNotFound,
+    /// `lowest-speed-limit` was reached.
+    TooSlowDownloadSpeed,
+    NetworkProblem,
+    /// There were unfinished downloads when aria2 was stopped.
+    InProgress,
+    CannotResume,
+    NotEnoughDiskSpace,
+    PieceLengthChanged,
+    DuplicateDownload,
+    DuplicateInfoHash,
+    FileAlreadyExists,
+    FileRenamingFailed,
+    FileOpenError,
+    FileCreateError,
+    FileIoError,
+    DirCreateError,
+    NameResolveError,
+    MetalinkParseError,
+    FtpProtocolError,
+    HttpProtocolError,
+    HttpTooManyRedirects,
+    HttpAuthFailed,
+    BencodeParseError,
+    BittorrentParseError,
+    MagnetParseError,
+    OptionError,
+    HttpServiceUnavailable,
+    JsonParseError,
+    Removed,
+    ChecksumError,
+    /// A code unknown to this version of the wrapper.
+    Other(i32),
+}
+
+impl Aria2ErrorCode {
+    pub fn code(&self) -> i32 {
+        use Aria2ErrorCode::*;
+        match *self {
+            Finished => 0,
+            UnknownError => 1,
+            TimeOut => 2,
+            ResourceNotFound => 3,
+            MaxFileNotFound => 4,
+            TooSlowDownloadSpeed => 5,
+            NetworkProblem => 6,
+            InProgress => 7,
+            CannotResume => 8,
+            NotEnoughDiskSpace => 9,
+            PieceLengthChanged => 10,
+            DuplicateDownload => 11,
+            DuplicateInfoHash => 12,
+            FileAlreadyExists => 13,
+            FileRenamingFailed => 14,
+            FileOpenError => 15,
+            FileCreateError => 16,
+            FileIoError => 17,
+            DirCreateError => 18,
+            NameResolveError => 19,
+            MetalinkParseError => 20,
+            FtpProtocolError => 21,
+            HttpProtocolError => 22,
+            HttpTooManyRedirects => 23,
+            HttpAuthFailed => 24,
+            BencodeParseError => 25,
+            BittorrentParseError => 26,
+            MagnetParseError => 27,
+            OptionError => 28,
+            HttpServiceUnavailable => 29,
+            JsonParseError => 30,
+            Removed => 31,
+            ChecksumError => 32,
+            Other(code) => code,
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        *self == Aria2ErrorCode::Finished
+    }
+
+    /// Whether the failure is likely transient, so adding the download again may succeed.
+    pub fn is_retryable(&self) -> bool {
+        use Aria2ErrorCode::*;
+        matches!(
+            self,
+            TimeOut
+                | TooSlowDownloadSpeed
+                | NetworkProblem
+                | InProgress
+                | NameResolveError
+                | FtpProtocolError
+                | HttpServiceUnavailable
+        )
+    }
+}
+
+impl From<i32> for Aria2ErrorCode {
+    fn from(code: i32) -> Self {
+        use Aria2ErrorCode::*;
+        match code {
+            0 => Finished,
+            1 => UnknownError,
+            2 => TimeOut,
+            3 => ResourceNotFound,
+            4 => MaxFileNotFound,
+            5 => TooSlowDownloadSpeed,
+            6 => NetworkProblem,
+            7 => InProgress,
+            8 => CannotResume,
+            9 => NotEnoughDiskSpace,
+            10 => PieceLengthChanged,
+            11 => DuplicateDownload,
+            12 => DuplicateInfoHash,
+            13 => FileAlreadyExists,
+            14 => FileRenamingFailed,
+            15 => FileOpenError,
+            16 => FileCreateError,
+            17 => FileIoError,
+            18 => DirCreateError,
+            19 => NameResolveError,
+            20 => MetalinkParseError,
+            21 => FtpProtocolError,
+            22 => HttpProtocolError,
+            23 => HttpTooManyRedirects,
+            24 => HttpAuthFailed,
+            25 => BencodeParseError,
+            26 => BittorrentParseError,
+            27 => MagnetParseError,
+            28 => OptionError,
+            29 => HttpServiceUnavailable,
+            30 => JsonParseError,
+            31 => Removed,
+            32 => ChecksumError,
+            code => Other(code),
+        }
+    }
+}
+
+impl From<Aria2ErrorCode> for i32 {
+    fn from(code: Aria2ErrorCode) -> Self {
+        code.code()
+    }
+}
+
+impl Display for Aria2ErrorCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use Aria2ErrorCode::*;
+        let message = match *self {
+            Finished => "all downloads were successful",
+            UnknownError => "an unknown error occurred",
+            TimeOut => "a timeout occurred",
+            ResourceNotFound => "a resource was not found",
+            MaxFileNotFound => "aria2 saw the specified number of \"resource not found\" errors",
+            TooSlowDownloadSpeed => "the download speed was too slow",
+            NetworkProblem => "a network problem occurred",
+            InProgress => "there were unfinished downloads",
+            CannotResume => "the remote server did not support resume when it was required",
+            NotEnoughDiskSpace => "there was not enough disk space available",
+            PieceLengthChanged => {
+                "the piece length was different from the one in the .aria2 control file"
+            }
+            DuplicateDownload => "the same file was being downloaded at that moment",
+            DuplicateInfoHash => "the same info hash torrent was being downloaded at that moment",
+            FileAlreadyExists => "the file already existed",
+            FileRenamingFailed => "renaming the file failed",
+            FileOpenError => "the existing file could not be opened",
+            FileCreateError => "a new file could not be created or an existing file truncated",
+            FileIoError => "a file I/O error occurred",
+            DirCreateError => "a directory could not be created",
+            NameResolveError => "name resolution failed",
+            MetalinkParseError => "the Metalink document could not be parsed",
+            FtpProtocolError => "an FTP command failed",
+            HttpProtocolError => "the HTTP response header was bad or unexpected",
+            HttpTooManyRedirects => "too many redirects occurred",
+            HttpAuthFailed => "HTTP authorization failed",
+            BencodeParseError => "a bencoded file could not be parsed",
+            BittorrentParseError => "the .torrent file was corrupted or missing information",
+            MagnetParseError => "the magnet URI was bad",
+            OptionError => "a bad or unrecognized option was given",
+            HttpServiceUnavailable => "the remote server was unable to handle the request",
+            JsonParseError => "the JSON-RPC request could not be parsed",
+            Removed => "the download was removed",
+            ChecksumError => "the checksum validation failed",
+            Other(code) => return write!(f, "unrecognized error code {}", code),
+        };
+        f.write_str(message)
+    }
+}
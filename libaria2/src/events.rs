@@ -1,38 +1,175 @@
-use crate::session::{Session, EVENT_SENDER};
+use crate::{
+    error_code::Aria2ErrorCode,
+    errors::{AriaError, Result},
+    gid::Gid,
+    session::{EventHandler, Session},
+};
 use libaria2_sys::{ffi, A2Gid};
-use log::error;
 
-#[derive(Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum DownloadEvent {
-    Started(A2Gid),
-    Paused(A2Gid),
-    Stopped(A2Gid),
-    Completed(A2Gid, bool),
-    Error(A2Gid),
+    Started(Gid),
+    Paused(Gid),
+    Stopped(Gid),
+    Completed(Gid, bool),
+    Error(Gid, Aria2ErrorCode),
+    /// The download was added to the session by one of the `add_*` methods.
+    Added(Gid),
+    /// The download was removed with [`Session::remove`].
+    Removed(Gid),
+    /// The download received some data, see [`Session::set_progress_interval`].
+    Progress {
+        gid: Gid,
+        completed: u64,
+        total: u64,
+        /// Download speed in bytes/sec.
+        speed: u32,
+    },
+    /// The download didn't receive anything for a while, see [`Session::set_stall_timeout`].
+    Stalled(Gid),
 }
 
-impl<U> Session<'_, U> {
-    pub(crate) fn static_event_callback(
-        _: ffi::SessionHandle,
-        event: ffi::DownloadEvent,
-        gid: A2Gid,
-        user_data: usize,
-    ) -> i32 {
-        let user_data = user_data as *mut std::ffi::c_void;
-        unsafe {
-            if let Err(e) = EVENT_SENDER.as_ref().unwrap().send((event, gid, user_data)) {
-                error!("{}", e);
-            }
-        };
+impl DownloadEvent {
+    /// The download concerned by the event.
+    pub fn gid(&self) -> Gid {
+        match *self {
+            DownloadEvent::Started(gid)
+            | DownloadEvent::Paused(gid)
+            | DownloadEvent::Stopped(gid)
+            | DownloadEvent::Completed(gid, _)
+            | DownloadEvent::Error(gid, _)
+            | DownloadEvent::Added(gid)
+            | DownloadEvent::Removed(gid)
+            | DownloadEvent::Progress { gid, .. }
+            | DownloadEvent::Stalled(gid) => gid,
+        }
+    }
+
+    pub fn kind(&self) -> EventKind {
+        match self {
+            DownloadEvent::Started(_) => EventKind::Start,
+            DownloadEvent::Paused(_) => EventKind::Pause,
+            DownloadEvent::Stopped(_) => EventKind::Stop,
+            DownloadEvent::Completed(..) => EventKind::Complete,
+            DownloadEvent::Error(..) => EventKind::Error,
+            DownloadEvent::Added(_) => EventKind::Add,
+            DownloadEvent::Removed(_) => EventKind::Remove,
+            DownloadEvent::Progress { .. } => EventKind::Progress,
+            DownloadEvent::Stalled(_) => EventKind::Stall,
+        }
+    }
+}
+
+/// Kind of a [`DownloadEvent`], used to filter the events given to a listener.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum EventKind {
+    Start,
+    Pause,
+    Stop,
+    Complete,
+    Error,
+    Add,
+    Remove,
+    Progress,
+    Stall,
+}
+
+/// Identifies a listener registered on a session, to remove it later.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ListenerHandle(usize);
+
+struct Listener<U> {
+    id: usize,
+    gid: Option<Gid>,
+    kind: Option<EventKind>,
+    handler: EventHandler<U>,
+}
+
+pub(crate) struct Listeners<U> {
+    listeners: Vec<Listener<U>>,
+    next_id: usize,
+}
+
+impl<U> Default for Listeners<U> {
+    fn default() -> Self {
+        Self {
+            listeners: Vec::new(),
+            next_id: 0,
+        }
+    }
+}
 
-        0
+impl<U> Listeners<U> {
+    pub(crate) fn add(
+        &mut self,
+        gid: Option<Gid>,
+        kind: Option<EventKind>,
+        handler: EventHandler<U>,
+    ) -> ListenerHandle {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.listeners.push(Listener {
+            id,
+            gid,
+            kind,
+            handler,
+        });
+        ListenerHandle(id)
+    }
+
+    fn remove(&mut self, handle: ListenerHandle) -> Result<()> {
+        let index = self
+            .listeners
+            .iter()
+            .position(|listener| listener.id == handle.0)
+            .ok_or(AriaError::InvalidCallbackHandle(handle.0))?;
+        self.listeners.remove(index);
+        Ok(())
+    }
+
+    fn dispatch(&mut self, user_data: &mut U, event: &DownloadEvent) {
+        let matching = self.listeners.iter_mut().filter(|listener| {
+            listener.gid.map_or(true, |gid| gid == event.gid())
+                && listener.kind.map_or(true, |kind| kind == event.kind())
+        });
+        for listener in matching {
+            (listener.handler)(user_data, event);
+        }
+    }
+}
+
+impl<U> Session<'_, U> {
+    /// Register a closure that will be called with the user data and every event,
+    /// before it is returned by `poll`.
+    pub fn on_event<F>(&mut self, handler: F) -> ListenerHandle
+    where
+        F: FnMut(&mut U, &DownloadEvent) + 'static,
+    {
+        self.listeners.add(None, None, Box::new(handler))
     }
 
-    pub(crate) fn handle_event(
+    /// Same as [`on_event`](Self::on_event) but only for the events of `gid` and of this `kind`,
+    /// `None` matches any of them, like in `session.on(None, EventKind::Error, handler)`.
+    pub fn on<F>(
         &mut self,
-        event: (ffi::DownloadEvent, A2Gid, *mut std::ffi::c_void),
-    ) {
-        let (event, gid, _) = event;
+        gid: impl Into<Option<Gid>>,
+        kind: impl Into<Option<EventKind>>,
+        handler: F,
+    ) -> ListenerHandle
+    where
+        F: FnMut(&mut U, &DownloadEvent) + 'static,
+    {
+        self.listeners
+            .add(gid.into(), kind.into(), Box::new(handler))
+    }
+
+    /// Unregister a listener, it fails if it was already removed.
+    pub fn remove_listener(&mut self, handle: ListenerHandle) -> Result<()> {
+        self.listeners.remove(handle)
+    }
+
+    pub(crate) fn handle_event(&mut self, event: (ffi::DownloadEvent, A2Gid)) {
+        let (event, gid) = (event.0, Gid::from(event.1));
 
         let event = match event {
             ffi::DownloadEvent::EVENT_ON_DOWNLOAD_START => DownloadEvent::Started(gid),
@@ -42,20 +179,28 @@ impl<U> Session<'_, U> {
             ffi::DownloadEvent::EVENT_ON_BT_DOWNLOAD_COMPLETE => {
                 DownloadEvent::Completed(gid, true)
             }
-            ffi::DownloadEvent::EVENT_ON_DOWNLOAD_ERROR => DownloadEvent::Error(gid),
+            ffi::DownloadEvent::EVENT_ON_DOWNLOAD_ERROR => {
+                DownloadEvent::Error(gid, self.error_code(gid))
+            }
             _ => unreachable!(),
         };
 
+        self.emit(event);
+    }
+
+    /// Give an event to the listeners and subscriptions, and queue it for `poll`.
+    pub(crate) fn emit(&mut self, event: DownloadEvent) {
+        self.listeners.dispatch(&mut self.user_data, &event);
+        self.subscribers.publish(&event);
         self.event_queue.push_back(event);
     }
-}
 
-/*impl<U> Session<'_, U> {
-    fn cast_user_data(ptr: *mut std::ffi::c_void) -> Box<&'static U> {
-        if ptr.is_null() {
-            unsafe { Box::new(MaybeUninit::zeroed().assume_init()) }
-        } else {
-            unsafe { Box::new(&mut *(ptr as *mut U)) }
-        }
+    fn error_code(&self, gid: Gid) -> Aria2ErrorCode {
+        let handle = unsafe { ffi::get_download_handle(self.handle, gid.raw()) };
+        handle
+            .as_ref()
+            .map_or(Aria2ErrorCode::UnknownError, |handle| {
+                unsafe { handle.error_code() }.into()
+            })
     }
-}*/
+}
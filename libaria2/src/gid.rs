@@ -0,0 +1,103 @@
+use libaria2_sys::A2Gid;
+use std::{
+    convert::TryFrom,
+    fmt::{Debug, Display, Formatter},
+    str::FromStr,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, Eq, PartialEq)]
+pub enum GidParseError {
+    #[error("A GID is 16 hex characters long, got {0}")]
+    InvalidLength(usize),
+    #[error("Invalid character in GID: {0:?}")]
+    InvalidCharacter(char),
+}
+
+/// Identifier of a download in a session.
+///
+/// It is displayed and parsed as 16 hex digits, like aria2 does.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "String", try_from = "String")
+)]
+pub struct Gid(A2Gid);
+
+impl Gid {
+    /// The invalid GID, aria2 never gives it to a download.
+    pub const NULL: Gid = Gid(0);
+
+    pub fn new(raw: A2Gid) -> Self {
+        Self(raw)
+    }
+
+    pub fn raw(&self) -> A2Gid {
+        self.0
+    }
+
+    pub fn is_null(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn to_hex(&self) -> String {
+        format!("{:016x}", self.0)
+    }
+}
+
+impl From<A2Gid> for Gid {
+    fn from(raw: A2Gid) -> Self {
+        Self(raw)
+    }
+}
+
+impl From<Gid> for A2Gid {
+    fn from(gid: Gid) -> Self {
+        gid.0
+    }
+}
+
+impl FromStr for Gid {
+    type Err = GidParseError;
+
+    /// Parse the 16 hex digits form, unlike aria2's `hexToGid` errors aren't turned into the
+    /// null GID.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(c) = s.chars().find(|c| !c.is_ascii_hexdigit()) {
+            return Err(GidParseError::InvalidCharacter(c));
+        }
+        if s.len() != 16 {
+            return Err(GidParseError::InvalidLength(s.len()));
+        }
+
+        // Only hex digits are left so this can't fail.
+        Ok(Self(A2Gid::from_str_radix(s, 16).unwrap()))
+    }
+}
+
+impl TryFrom<String> for Gid {
+    type Error = GidParseError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Gid> for String {
+    fn from(gid: Gid) -> Self {
+        gid.to_hex()
+    }
+}
+
+impl Display for Gid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl Debug for Gid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Gid({:016x})", self.0)
+    }
+}
@@ -0,0 +1,127 @@
+use std::{
+    convert::TryInto,
+    fmt::{Debug, Display, Formatter},
+    str::FromStr,
+};
+use thiserror::Error;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+#[derive(Debug, Error, Clone, Eq, PartialEq)]
+pub enum InfoHashParseError {
+    #[error("An info hash is 40 hex or 32 base32 characters long, got {0}")]
+    InvalidLength(usize),
+    #[error("Invalid character in info hash: {0:?}")]
+    InvalidCharacter(char),
+    #[error("Not a BitTorrent info hash URN, expected it to start with urn:btih:")]
+    NotBtih,
+}
+
+/// SHA-1 info hash of a torrent.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct InfoHash([u8; 20]);
+
+impl InfoHash {
+    pub fn new(bytes: [u8; 20]) -> Self {
+        Self(bytes)
+    }
+
+    /// Build an info hash from its raw binary form, as given by aria2.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        bytes.try_into().ok().map(Self)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 20] {
+        &self.0
+    }
+
+    /// Lowercase hex form, the one used by aria2 and most clients.
+    pub fn to_hex(&self) -> String {
+        self.0.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Uppercase base32 form, found in some magnet links.
+    pub fn to_base32(&self) -> String {
+        let mut out = String::with_capacity(32);
+        // 20 bytes are exactly 32 groups of 5 bits so no padding is needed.
+        for chunk in self.0.chunks(5) {
+            let bits = chunk.iter().fold(0u64, |acc, &b| acc << 8 | b as u64);
+            for i in (0..8).rev() {
+                out.push(BASE32_ALPHABET[(bits >> (i * 5)) as usize & 0x1f] as char);
+            }
+        }
+        out
+    }
+
+    /// Parse the `xt` parameter of a magnet link, like `urn:btih:<hex or base32>`.
+    pub fn from_magnet_xt(xt: &str) -> Result<Self, InfoHashParseError> {
+        let prefix = "urn:btih:";
+        let is_btih = xt
+            .get(..prefix.len())
+            .map_or(false, |p| p.eq_ignore_ascii_case(prefix));
+        if !is_btih {
+            return Err(InfoHashParseError::NotBtih);
+        }
+        xt[prefix.len()..].parse()
+    }
+
+    fn from_hex(s: &str) -> Result<Self, InfoHashParseError> {
+        let mut bytes = [0; 20];
+        for (byte, pair) in bytes.iter_mut().zip(s.as_bytes().chunks(2)) {
+            let digit = |c: u8| {
+                (c as char)
+                    .to_digit(16)
+                    .ok_or(InfoHashParseError::InvalidCharacter(c as char))
+            };
+            *byte = (digit(pair[0])? << 4 | digit(pair[1])?) as u8;
+        }
+        Ok(Self(bytes))
+    }
+
+    fn from_base32(s: &str) -> Result<Self, InfoHashParseError> {
+        let mut bytes = [0; 20];
+        for (out, chunk) in bytes.chunks_mut(5).zip(s.as_bytes().chunks(8)) {
+            let mut bits = 0u64;
+            for &c in chunk {
+                let value = BASE32_ALPHABET
+                    .iter()
+                    .position(|&a| a == c.to_ascii_uppercase())
+                    .ok_or(InfoHashParseError::InvalidCharacter(c as char))?;
+                bits = bits << 5 | value as u64;
+            }
+            for (i, byte) in out.iter_mut().enumerate() {
+                *byte = (bits >> ((4 - i) * 8)) as u8;
+            }
+        }
+        Ok(Self(bytes))
+    }
+}
+
+impl FromStr for InfoHash {
+    type Err = InfoHashParseError;
+
+    /// Parse either the hex or base32 form.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(c) = s.chars().find(|c| !c.is_ascii()) {
+            return Err(InfoHashParseError::InvalidCharacter(c));
+        }
+
+        match s.len() {
+            40 => Self::from_hex(s),
+            32 => Self::from_base32(s),
+            len => Err(InfoHashParseError::InvalidLength(len)),
+        }
+    }
+}
+
+impl Display for InfoHash {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl Debug for InfoHash {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "InfoHash({})", self.to_hex())
+    }
+}
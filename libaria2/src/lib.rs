@@ -1,33 +1,87 @@
 use std::sync::atomic::AtomicBool;
 
 pub mod actions;
+#[cfg(feature = "async")]
+pub mod async_session;
+pub mod bitfield;
 pub mod download_handle;
+pub mod download_info;
+pub mod error_code;
 pub mod events;
+pub mod gid;
+pub mod info_hash;
+pub mod options;
+mod path;
+pub mod progress;
+pub mod request;
 pub mod session;
+pub mod stats;
+pub mod subscription;
+pub mod wait;
 
 pub(crate) static ARIA_STARTED: AtomicBool = AtomicBool::new(false);
 
 pub mod errors {
+    use crate::{download_handle::DownloadStatus, gid::Gid, options::catalog::OptionError};
+    use std::path::PathBuf;
     use thiserror::Error;
 
     pub type Result<T> = std::result::Result<T, AriaError>;
 
     #[derive(Debug, Error)]
     pub enum AriaError {
-        #[error("Aria2 has already been started once in this process !")]
+        #[error("Aria2 is already initialized in this process !")]
         AlreadyInitialized,
+        #[error("aria2 failed to initialize (returned {0})")]
+        InitError(i32),
         #[error("The given handle ({0}) couldn't be used to unregister the listener, it must be invalid !")]
         InvalidCallbackHandle(usize),
-        #[error("Run error code")]
+        #[error("aria2 failed to run the session (returned {0})")]
         RunError(i32),
-        #[error("Add error: {0}")]
+        #[error("aria2 refused to add the download (returned {0})")]
         AddError(i32),
+        #[error("aria2 rejected the options")]
+        InvalidOptions,
+        #[error(transparent)]
+        InvalidOption(#[from] OptionError),
+        #[error("No download with GID {0}")]
+        UnknownGid(Gid),
+        #[error("Can't {action} download {gid} while it is {status:?}")]
+        InvalidDownloadState {
+            action: &'static str,
+            gid: Gid,
+            status: DownloadStatus,
+        },
+        #[error("aria2 couldn't create a session with the option {key}={value}")]
+        SessionCreateError { key: String, value: String },
+        #[error("aria2 couldn't create a session")]
+        SessionCreateFailed,
+        #[error("The session has been shut down")]
+        SessionClosed,
+        #[error("The call panicked on the session thread")]
+        CommandPanicked,
+        #[error("The path {0:?} can't be given to aria2 on this platform")]
+        InvalidPath(PathBuf),
     }
 }
 
 pub mod prelude {
     pub use crate::{
+        actions::Position,
+        bitfield::PieceBitfield,
+        download_info::DownloadInfo,
+        error_code::Aria2ErrorCode,
         errors::Result,
-        session::{Aria2Context, Session},
+        gid::Gid,
+        info_hash::InfoHash,
+        options::Options,
+        request::{DownloadOptions, DownloadRequest},
+        session::{Aria2Context, Session, SessionBuilder},
+        stats::{DownloadStats, GlobalStat},
+        subscription::EventSubscription,
+        wait::DownloadOutcome,
     };
+
+    #[cfg(feature = "async")]
+    pub use crate::async_session::{AsyncSession, AsyncSessionHandle};
 }
@@ -0,0 +1,446 @@
+//! Built-in description of the options understood by aria2, used to validate options before
+//! handing them to aria2 which only answers with -1 when something is wrong.
+
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum OptionError {
+    #[error("The option {0} can't be changed at runtime")]
+    NotChangeableAtRuntime(String),
+    #[error("The option {0} can't be set for a single download")]
+    NotPerDownload(String),
+    #[error("Invalid value {value:?} for option {key}, expected {expected}")]
+    InvalidValue {
+        key: String,
+        value: String,
+        expected: String,
+    },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ValueType {
+    /// `true` or `false`.
+    Bool,
+    /// An integer within inclusive bounds.
+    Integer {
+        min: i64,
+        max: i64,
+    },
+    /// A number of bytes within inclusive bounds, the `K` and `M` suffixes are accepted.
+    Size {
+        min: u64,
+        max: u64,
+    },
+    /// A decimal number within inclusive bounds.
+    Float {
+        min: f64,
+        max: f64,
+    },
+    /// One of a fixed set of values.
+    Enum(&'static [&'static str]),
+    /// A hash algorithm and a hex digest, like `sha-1=<digest>`.
+    Checksum,
+    String,
+    Path,
+}
+
+/// What happens when changing an option of a download after it has been added.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DownloadChange {
+    /// It can't be changed.
+    Forbidden,
+    /// It can be changed but an active download will restart.
+    Restart,
+    /// It is applied to active downloads right away.
+    Live,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum OptionScope {
+    /// Only for the whole session, can't be changed once it is created.
+    Global,
+    /// Only for the whole session, can be changed with `change_global_option`.
+    GlobalChangeable,
+    /// Can be set for a single download, the global value is used as the default.
+    PerDownload(DownloadChange),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct OptionSpec {
+    pub name: &'static str,
+    pub value_type: ValueType,
+    pub default: Option<&'static str>,
+    pub scope: OptionScope,
+}
+
+/// Per-download options that can't be changed with `change_global_option`.
+const NOT_GLOBALLY_CHANGEABLE: &[&str] = &["checksum", "index-out", "out", "pause", "select-file"];
+
+impl OptionSpec {
+    pub fn is_per_download(&self) -> bool {
+        matches!(self.scope, OptionScope::PerDownload(_))
+    }
+
+    /// Whether the option is accepted by `change_global_option`.
+    pub fn is_globally_changeable(&self) -> bool {
+        match self.scope {
+            OptionScope::Global => false,
+            OptionScope::GlobalChangeable => true,
+            OptionScope::PerDownload(_) => !NOT_GLOBALLY_CHANGEABLE.contains(&self.name),
+        }
+    }
+
+    /// Whether the option is accepted by `change_option` for a download already added.
+    pub fn is_changeable_while_active(&self) -> bool {
+        matches!(
+            self.scope,
+            OptionScope::PerDownload(DownloadChange::Restart | DownloadChange::Live)
+        )
+    }
+
+    pub fn validate(&self, value: &str) -> Result<(), OptionError> {
+        let valid = match self.value_type {
+            ValueType::Bool => value == "true" || value == "false",
+            ValueType::Integer { min, max } => {
+                value.parse::<i64>().map_or(false, |v| v >= min && v <= max)
+            }
+            ValueType::Size { min, max } => {
+                parse_size(value).map_or(false, |v| v >= min && v <= max)
+            }
+            ValueType::Float { min, max } => {
+                value.parse::<f64>().map_or(false, |v| v >= min && v <= max)
+            }
+            ValueType::Enum(values) => values.contains(&value),
+            ValueType::Checksum => is_checksum(value),
+            ValueType::String => true,
+            ValueType::Path => !value.is_empty(),
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(OptionError::InvalidValue {
+                key: self.name.to_string(),
+                value: value.to_string(),
+                expected: self.expected(),
+            })
+        }
+    }
+
+    fn expected(&self) -> String {
+        match self.value_type {
+            ValueType::Bool => "true or false".to_string(),
+            ValueType::Integer { min, max } if max == i64::MAX => {
+                format!("an integer greater than or equal to {}", min)
+            }
+            ValueType::Integer { min, max } => format!("an integer between {} and {}", min, max),
+            ValueType::Size { min, max } if max == u64::MAX => {
+                format!("a size greater than or equal to {}", min)
+            }
+            ValueType::Size { min, max } => format!("a size between {} and {}", min, max),
+            ValueType::Float { min, max } if max == f64::MAX => {
+                format!("a number greater than or equal to {}", min)
+            }
+            ValueType::Float { min, max } => format!("a number between {} and {}", min, max),
+            ValueType::Enum(values) => format!("one of {}", values.join(", ")),
+            ValueType::Checksum => format!(
+                "<algorithm>=<hex digest> with an algorithm among {}",
+                HASH_ALGORITHMS.join(", ")
+            ),
+            ValueType::String => "a string".to_string(),
+            ValueType::Path => "a non-empty path".to_string(),
+        }
+    }
+}
+
+/// Hash algorithms accepted by the `checksum` option.
+const HASH_ALGORITHMS: &[&str] = &[
+    "sha-1", "sha-224", "sha-256", "sha-384", "sha-512", "md5", "adler32",
+];
+
+fn is_checksum(value: &str) -> bool {
+    let mut parts = value.splitn(2, '=');
+    let algorithm = parts.next().unwrap_or_default();
+    let digest = parts.next().unwrap_or_default();
+    HASH_ALGORITHMS.contains(&algorithm)
+        && !digest.is_empty()
+        && digest.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Parse a size like aria2 does, `1K` is 1024 bytes and `1M` is 1024K.
+fn parse_size(value: &str) -> Option<u64> {
+    let (number, multiplier) = match value.chars().last()? {
+        'K' | 'k' => (&value[..value.len() - 1], 1024),
+        'M' | 'm' => (&value[..value.len() - 1], 1024 * 1024),
+        _ => (value, 1),
+    };
+    number.parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
+/// Find the description of an option by its name.
+pub fn lookup(name: &str) -> Option<&'static OptionSpec> {
+    CATALOG.iter().find(|spec| spec.name == name)
+}
+
+/// Every option known to the catalog.
+pub fn all() -> &'static [OptionSpec] {
+    CATALOG
+}
+
+const M: u64 = 1024 * 1024;
+const BOOL: ValueType = ValueType::Bool;
+const STRING: ValueType = ValueType::String;
+const PATH: ValueType = ValueType::Path;
+const GLOBAL: OptionScope = OptionScope::Global;
+const GLOBAL_CHANGEABLE: OptionScope = OptionScope::GlobalChangeable;
+const DOWNLOAD: OptionScope = OptionScope::PerDownload(DownloadChange::Restart);
+const DOWNLOAD_LIVE: OptionScope = OptionScope::PerDownload(DownloadChange::Live);
+const DOWNLOAD_FIXED: OptionScope = OptionScope::PerDownload(DownloadChange::Forbidden);
+
+const fn int(min: i64) -> ValueType {
+    ValueType::Integer { min, max: i64::MAX }
+}
+
+const fn int_range(min: i64, max: i64) -> ValueType {
+    ValueType::Integer { min, max }
+}
+
+const fn size(min: u64) -> ValueType {
+    ValueType::Size { min, max: u64::MAX }
+}
+
+const fn float(min: f64) -> ValueType {
+    ValueType::Float { min, max: f64::MAX }
+}
+
+const fn spec(
+    name: &'static str,
+    value_type: ValueType,
+    default: Option<&'static str>,
+    scope: OptionScope,
+) -> OptionSpec {
+    OptionSpec {
+        name,
+        value_type,
+        default,
+        scope,
+    }
+}
+
+const LOG_LEVELS: &[&str] = &["debug", "info", "notice", "warn", "error"];
+const FOLLOW: &[&str] = &["true", "false", "mem"];
+
+#[rustfmt::skip]
+static CATALOG: &[OptionSpec] = &[
+    // Basic
+    spec("dir", PATH, None, DOWNLOAD),
+    spec("input-file", PATH, None, GLOBAL),
+    spec("log", PATH, None, GLOBAL_CHANGEABLE),
+    spec("max-concurrent-downloads", int(1), Some("5"), GLOBAL_CHANGEABLE),
+    spec("check-integrity", BOOL, Some("false"), DOWNLOAD),
+    spec("continue", BOOL, Some("false"), DOWNLOAD),
+
+    // HTTP/FTP/SFTP
+    spec("all-proxy", STRING, None, DOWNLOAD),
+    spec("all-proxy-passwd", STRING, None, DOWNLOAD),
+    spec("all-proxy-user", STRING, None, DOWNLOAD),
+    spec("checksum", ValueType::Checksum, None, DOWNLOAD),
+    spec("connect-timeout", int_range(1, 600), Some("60"), DOWNLOAD),
+    spec("dry-run", BOOL, Some("false"), DOWNLOAD_FIXED),
+    spec("lowest-speed-limit", size(0), Some("0"), DOWNLOAD),
+    spec("max-connection-per-server", int_range(1, 16), Some("1"), DOWNLOAD),
+    spec("max-file-not-found", int(0), Some("0"), DOWNLOAD),
+    spec("max-tries", int(0), Some("5"), DOWNLOAD),
+    spec("min-split-size", ValueType::Size { min: M, max: 1024 * M }, Some("20M"), DOWNLOAD),
+    spec("netrc-path", PATH, None, GLOBAL),
+    spec("no-netrc", BOOL, Some("false"), DOWNLOAD),
+    spec("no-proxy", STRING, None, DOWNLOAD),
+    spec("out", PATH, None, DOWNLOAD),
+    spec("proxy-method", ValueType::Enum(&["get", "tunnel"]), Some("get"), DOWNLOAD),
+    spec("remote-time", BOOL, Some("false"), DOWNLOAD),
+    spec("reuse-uri", BOOL, Some("true"), DOWNLOAD),
+    spec("retry-wait", int_range(0, 600), Some("0"), DOWNLOAD),
+    spec("server-stat-of", PATH, None, GLOBAL_CHANGEABLE),
+    spec("server-stat-if", PATH, None, GLOBAL),
+    spec("server-stat-timeout", int(0), Some("86400"), GLOBAL),
+    spec("split", int(1), Some("5"), DOWNLOAD),
+    spec("stream-piece-selector", ValueType::Enum(&["default", "inorder", "random", "geom"]), Some("default"), DOWNLOAD),
+    spec("timeout", int_range(1, 600), Some("60"), DOWNLOAD),
+    spec("uri-selector", ValueType::Enum(&["inorder", "feedback", "adaptive"]), Some("feedback"), DOWNLOAD),
+
+    // HTTP
+    spec("ca-certificate", PATH, None, GLOBAL),
+    spec("certificate", PATH, None, GLOBAL),
+    spec("check-certificate", BOOL, Some("true"), DOWNLOAD),
+    spec("http-accept-gzip", BOOL, Some("false"), DOWNLOAD),
+    spec("http-auth-challenge", BOOL, Some("false"), DOWNLOAD),
+    spec("http-no-cache", BOOL, Some("false"), DOWNLOAD),
+    spec("http-user", STRING, None, DOWNLOAD),
+    spec("http-passwd", STRING, None, DOWNLOAD),
+    spec("http-proxy", STRING, None, DOWNLOAD),
+    spec("http-proxy-passwd", STRING, None, DOWNLOAD),
+    spec("http-proxy-user", STRING, None, DOWNLOAD),
+    spec("https-proxy", STRING, None, DOWNLOAD),
+    spec("https-proxy-passwd", STRING, None, DOWNLOAD),
+    spec("https-proxy-user", STRING, None, DOWNLOAD),
+    spec("private-key", PATH, None, GLOBAL),
+    spec("referer", STRING, None, DOWNLOAD),
+    spec("enable-http-keep-alive", BOOL, Some("true"), DOWNLOAD),
+    spec("enable-http-pipelining", BOOL, Some("false"), DOWNLOAD),
+    spec("header", STRING, None, DOWNLOAD),
+    spec("load-cookies", PATH, None, GLOBAL),
+    spec("save-cookies", PATH, None, GLOBAL_CHANGEABLE),
+    spec("use-head", BOOL, Some("false"), DOWNLOAD),
+    spec("user-agent", STRING, None, DOWNLOAD),
+
+    // FTP/SFTP
+    spec("ftp-user", STRING, None, DOWNLOAD),
+    spec("ftp-passwd", STRING, None, DOWNLOAD),
+    spec("ftp-pasv", BOOL, Some("true"), DOWNLOAD),
+    spec("ftp-proxy", STRING, None, DOWNLOAD),
+    spec("ftp-proxy-passwd", STRING, None, DOWNLOAD),
+    spec("ftp-proxy-user", STRING, None, DOWNLOAD),
+    spec("ftp-type", ValueType::Enum(&["binary", "ascii"]), Some("binary"), DOWNLOAD),
+    spec("ftp-reuse-connection", BOOL, Some("true"), DOWNLOAD),
+    spec("ssh-host-key-md", STRING, None, DOWNLOAD),
+
+    // BitTorrent/Metalink
+    spec("select-file", STRING, None, DOWNLOAD),
+    spec("show-files", BOOL, Some("false"), GLOBAL),
+
+    // BitTorrent
+    spec("bt-detach-seed-only", BOOL, Some("false"), GLOBAL),
+    spec("bt-enable-hook-after-hash-check", BOOL, Some("true"), DOWNLOAD),
+    spec("bt-enable-lpd", BOOL, Some("false"), DOWNLOAD),
+    spec("bt-exclude-tracker", STRING, None, DOWNLOAD),
+    spec("bt-external-ip", STRING, None, DOWNLOAD),
+    spec("bt-force-encryption", BOOL, Some("false"), DOWNLOAD),
+    spec("bt-hash-check-seed", BOOL, Some("true"), DOWNLOAD),
+    spec("bt-load-saved-metadata", BOOL, Some("false"), DOWNLOAD),
+    spec("bt-lpd-interface", STRING, None, GLOBAL),
+    spec("bt-max-open-files", int(1), Some("100"), GLOBAL_CHANGEABLE),
+    spec("bt-max-peers", int(0), Some("55"), DOWNLOAD_LIVE),
+    spec("bt-metadata-only", BOOL, Some("false"), DOWNLOAD),
+    spec("bt-min-crypto-level", ValueType::Enum(&["plain", "arc4"]), Some("plain"), DOWNLOAD),
+    spec("bt-prioritize-piece", STRING, None, DOWNLOAD),
+    spec("bt-remove-unselected-file", BOOL, Some("false"), DOWNLOAD_LIVE),
+    spec("bt-require-crypto", BOOL, Some("false"), DOWNLOAD),
+    spec("bt-request-peer-speed-limit", size(0), Some("50K"), DOWNLOAD_LIVE),
+    spec("bt-save-metadata", BOOL, Some("false"), DOWNLOAD),
+    spec("bt-seed-unverified", BOOL, Some("false"), DOWNLOAD),
+    spec("bt-stop-timeout", int(0), Some("0"), DOWNLOAD),
+    spec("bt-tracker", STRING, None, DOWNLOAD),
+    spec("bt-tracker-connect-timeout", int_range(1, 600), Some("60"), DOWNLOAD),
+    spec("bt-tracker-interval", int(0), Some("0"), DOWNLOAD),
+    spec("bt-tracker-timeout", int_range(1, 600), Some("60"), DOWNLOAD),
+    spec("dht-entry-point", STRING, None, GLOBAL),
+    spec("dht-entry-point6", STRING, None, GLOBAL),
+    spec("dht-file-path", PATH, None, GLOBAL),
+    spec("dht-file-path6", PATH, None, GLOBAL),
+    spec("dht-listen-addr6", STRING, None, GLOBAL),
+    spec("dht-listen-port", STRING, Some("6881-6999"), GLOBAL),
+    spec("dht-message-timeout", int_range(1, 60), Some("10"), GLOBAL),
+    spec("enable-dht", BOOL, Some("true"), GLOBAL),
+    spec("enable-dht6", BOOL, Some("false"), GLOBAL),
+    spec("enable-peer-exchange", BOOL, Some("true"), DOWNLOAD),
+    spec("follow-torrent", ValueType::Enum(FOLLOW), Some("true"), DOWNLOAD),
+    spec("index-out", STRING, None, DOWNLOAD),
+    spec("listen-port", STRING, Some("6881-6999"), GLOBAL),
+    spec("max-overall-upload-limit", size(0), Some("0"), GLOBAL_CHANGEABLE),
+    spec("max-upload-limit", size(0), Some("0"), DOWNLOAD_LIVE),
+    spec("peer-id-prefix", STRING, None, GLOBAL),
+    spec("peer-agent", STRING, None, GLOBAL),
+    spec("seed-ratio", float(0.0), Some("1.0"), DOWNLOAD),
+    spec("seed-time", float(0.0), None, DOWNLOAD),
+    spec("torrent-file", PATH, None, GLOBAL),
+
+    // Metalink
+    spec("follow-metalink", ValueType::Enum(FOLLOW), Some("true"), DOWNLOAD),
+    spec("metalink-base-uri", STRING, None, DOWNLOAD_FIXED),
+    spec("metalink-file", PATH, None, GLOBAL),
+    spec("metalink-language", STRING, None, DOWNLOAD),
+    spec("metalink-location", STRING, None, DOWNLOAD),
+    spec("metalink-os", STRING, None, DOWNLOAD),
+    spec("metalink-version", STRING, None, DOWNLOAD),
+    spec("metalink-preferred-protocol", ValueType::Enum(&["http", "https", "ftp", "none"]), Some("none"), DOWNLOAD),
+    spec("metalink-enable-unique-protocol", BOOL, Some("true"), DOWNLOAD),
+
+    // RPC
+    spec("enable-rpc", BOOL, Some("false"), GLOBAL),
+    spec("pause-metadata", BOOL, Some("false"), DOWNLOAD),
+    spec("rpc-allow-origin-all", BOOL, Some("false"), GLOBAL),
+    spec("rpc-certificate", PATH, None, GLOBAL),
+    spec("rpc-listen-all", BOOL, Some("false"), GLOBAL),
+    spec("rpc-listen-port", int_range(1024, 65535), Some("6800"), GLOBAL),
+    spec("rpc-max-request-size", size(0), Some("2M"), GLOBAL),
+    spec("rpc-passwd", STRING, None, GLOBAL),
+    spec("rpc-private-key", PATH, None, GLOBAL),
+    spec("rpc-save-upload-metadata", BOOL, Some("true"), DOWNLOAD_FIXED),
+    spec("rpc-secret", STRING, None, GLOBAL),
+    spec("rpc-secure", BOOL, Some("false"), GLOBAL),
+    spec("rpc-user", STRING, None, GLOBAL),
+
+    // Advanced
+    spec("allow-overwrite", BOOL, Some("false"), DOWNLOAD),
+    spec("allow-piece-length-change", BOOL, Some("false"), DOWNLOAD),
+    spec("always-resume", BOOL, Some("true"), DOWNLOAD),
+    spec("async-dns", BOOL, Some("true"), DOWNLOAD),
+    spec("async-dns-server", STRING, None, GLOBAL),
+    spec("auto-file-renaming", BOOL, Some("true"), DOWNLOAD),
+    spec("auto-save-interval", int_range(0, 600), Some("60"), GLOBAL),
+    spec("conditional-get", BOOL, Some("false"), DOWNLOAD),
+    spec("conf-path", PATH, None, GLOBAL),
+    spec("console-log-level", ValueType::Enum(LOG_LEVELS), Some("notice"), GLOBAL),
+    spec("content-disposition-default-utf8", BOOL, Some("false"), DOWNLOAD),
+    spec("daemon", BOOL, Some("false"), GLOBAL),
+    spec("deferred-input", BOOL, Some("false"), GLOBAL),
+    spec("disable-ipv6", BOOL, Some("false"), GLOBAL),
+    spec("disk-cache", size(0), Some("16M"), GLOBAL),
+    spec("download-result", ValueType::Enum(&["default", "full", "hide"]), Some("default"), GLOBAL_CHANGEABLE),
+    spec("dscp", int_range(0, 63), None, GLOBAL),
+    spec("rlimit-nofile", int(1), None, GLOBAL),
+    spec("enable-color", BOOL, Some("true"), GLOBAL),
+    spec("enable-mmap", BOOL, Some("false"), DOWNLOAD),
+    spec("event-poll", ValueType::Enum(&["epoll", "kqueue", "port", "poll", "select"]), None, GLOBAL),
+    spec("file-allocation", ValueType::Enum(&["none", "prealloc", "trunc", "falloc"]), Some("prealloc"), DOWNLOAD),
+    spec("force-save", BOOL, Some("false"), DOWNLOAD_LIVE),
+    spec("gid", STRING, None, DOWNLOAD),
+    spec("hash-check-only", BOOL, Some("false"), DOWNLOAD),
+    spec("human-readable", BOOL, Some("true"), GLOBAL),
+    spec("interface", STRING, None, GLOBAL),
+    spec("keep-unfinished-download-result", BOOL, Some("true"), GLOBAL_CHANGEABLE),
+    spec("log-level", ValueType::Enum(LOG_LEVELS), Some("debug"), GLOBAL_CHANGEABLE),
+    spec("max-download-limit", size(0), Some("0"), DOWNLOAD_LIVE),
+    spec("max-download-result", int(0), Some("1000"), GLOBAL_CHANGEABLE),
+    spec("max-mmap-limit", size(0), None, DOWNLOAD),
+    spec("max-overall-download-limit", size(0), Some("0"), GLOBAL_CHANGEABLE),
+    spec("max-resume-failure-tries", int(0), Some("0"), DOWNLOAD),
+    spec("min-tls-version", ValueType::Enum(&["TLSv1.1", "TLSv1.2", "TLSv1.3"]), Some("TLSv1.2"), GLOBAL),
+    spec("multiple-interface", STRING, None, GLOBAL),
+    spec("no-conf", BOOL, Some("false"), GLOBAL),
+    spec("no-file-allocation-limit", size(0), Some("5M"), DOWNLOAD),
+    spec("optimize-concurrent-downloads", STRING, Some("false"), GLOBAL_CHANGEABLE),
+    spec("parameterized-uri", BOOL, Some("false"), DOWNLOAD_FIXED),
+    spec("pause", BOOL, Some("false"), DOWNLOAD_FIXED),
+    spec("piece-length", ValueType::Size { min: M, max: 1024 * M }, Some("1M"), DOWNLOAD_FIXED),
+    spec("quiet", BOOL, Some("false"), GLOBAL),
+    spec("realtime-chunk-checksum", BOOL, Some("true"), DOWNLOAD),
+    spec("remove-control-file", BOOL, Some("false"), DOWNLOAD),
+    spec("save-not-found", BOOL, Some("true"), GLOBAL),
+    spec("save-session", PATH, None, GLOBAL_CHANGEABLE),
+    spec("save-session-interval", int(0), Some("0"), GLOBAL),
+    spec("socket-recv-buffer-size", ValueType::Size { min: 0, max: 16 * M }, Some("0"), GLOBAL),
+    spec("stop", int(0), Some("0"), GLOBAL),
+    spec("stop-with-process", int(0), None, GLOBAL),
+    spec("summary-interval", int(0), Some("60"), GLOBAL),
+    spec("truncate-console-readout", BOOL, Some("true"), GLOBAL),
+    spec("show-console-readout", BOOL, Some("true"), GLOBAL),
+
+    // Event hooks
+    spec("on-bt-download-complete", PATH, None, GLOBAL),
+    spec("on-download-complete", PATH, None, GLOBAL),
+    spec("on-download-error", PATH, None, GLOBAL),
+    spec("on-download-pause", PATH, None, GLOBAL),
+    spec("on-download-start", PATH, None, GLOBAL),
+    spec("on-download-stop", PATH, None, GLOBAL),
+];
@@ -0,0 +1,48 @@
+//! Conversions between paths and the raw bytes aria2 works with.
+//!
+//! aria2 doesn't care about encodings and treats paths as plain byte strings, so on unix they
+//! are passed around untouched. Elsewhere aria2 expects UTF-8.
+//!
+//! Option values cross the bridge as UTF-8 strings, so a path given as an option must be valid
+//! UTF-8 on every platform.
+
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+use crate::errors::Result;
+
+#[cfg(unix)]
+pub(crate) fn os_string_from_bytes(bytes: &[u8]) -> OsString {
+    use std::os::unix::ffi::OsStrExt;
+    std::ffi::OsStr::from_bytes(bytes).to_os_string()
+}
+
+#[cfg(not(unix))]
+pub(crate) fn os_string_from_bytes(bytes: &[u8]) -> OsString {
+    String::from_utf8_lossy(bytes).into_owned().into()
+}
+
+pub(crate) fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    os_string_from_bytes(bytes).into()
+}
+
+#[cfg(unix)]
+pub(crate) fn path_to_bytes(path: &Path) -> Result<&[u8]> {
+    use std::os::unix::ffi::OsStrExt;
+    Ok(path.as_os_str().as_bytes())
+}
+
+#[cfg(not(unix))]
+pub(crate) fn path_to_bytes(path: &Path) -> Result<&[u8]> {
+    path.to_str()
+        .map(str::as_bytes)
+        .ok_or_else(|| crate::errors::AriaError::InvalidPath(path.to_path_buf()))
+}
+
+/// A path given as an option value, it can't be passed as raw bytes.
+pub(crate) fn path_to_option(path: &Path) -> Result<&str> {
+    path.to_str()
+        .ok_or_else(|| crate::errors::AriaError::InvalidPath(path.to_path_buf()))
+}
@@ -0,0 +1,133 @@
+use libaria2_sys::ffi;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use crate::{events::DownloadEvent, gid::Gid, session::Session, stats::DownloadStats};
+
+/// How often the active downloads are checked for stalls when progress events are disabled.
+const STALL_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+struct Tracked {
+    completed: u64,
+    last_moving: Instant,
+    stalled: bool,
+}
+
+/// State used to synthesize [`DownloadEvent::Progress`] and [`DownloadEvent::Stalled`] from
+/// successive stats of the active downloads.
+///
+/// Sessions use one internally, see [`Session::set_progress_interval`].
+#[derive(Default)]
+pub struct ProgressTracker {
+    interval: Option<Duration>,
+    stall_timeout: Option<Duration>,
+    last_check: Option<Instant>,
+    downloads: HashMap<Gid, Tracked>,
+}
+
+impl ProgressTracker {
+    pub fn new(interval: Option<Duration>, stall_timeout: Option<Duration>) -> Self {
+        Self {
+            interval,
+            stall_timeout,
+            ..Default::default()
+        }
+    }
+
+    fn check_interval(&self) -> Option<Duration> {
+        match (self.interval, self.stall_timeout) {
+            (Some(interval), _) => Some(interval),
+            (None, Some(_)) => Some(STALL_CHECK_INTERVAL),
+            (None, None) => None,
+        }
+    }
+
+    /// Whether the downloads should be checked now, it is throttled to the configured interval.
+    pub fn is_due(&mut self, now: Instant) -> bool {
+        let interval = match self.check_interval() {
+            Some(interval) => interval,
+            None => return false,
+        };
+        if matches!(self.last_check, Some(last) if now.duration_since(last) < interval) {
+            return false;
+        }
+        self.last_check = Some(now);
+        true
+    }
+
+    /// Compare `stats`, the active downloads at `now`, with the previous ones and return the
+    /// events to emit. Downloads missing from `stats` are forgotten.
+    pub fn update(&mut self, now: Instant, stats: &[DownloadStats]) -> Vec<DownloadEvent> {
+        let mut events = Vec::new();
+        self.downloads
+            .retain(|gid, _| stats.iter().any(|s| s.gid == *gid));
+
+        for s in stats {
+            let tracked = self.downloads.entry(s.gid).or_insert(Tracked {
+                completed: 0,
+                last_moving: now,
+                stalled: false,
+            });
+
+            if self.interval.is_some() && s.completed_len != tracked.completed {
+                events.push(DownloadEvent::Progress {
+                    gid: s.gid,
+                    completed: s.completed_len,
+                    total: s.total_len,
+                    speed: s.download_speed,
+                });
+            }
+
+            // A torrent that is seeding doesn't download anything but isn't stalled.
+            let finished = s.total_len > 0 && s.completed_len == s.total_len;
+            if s.download_speed > 0 || s.completed_len != tracked.completed || finished {
+                tracked.last_moving = now;
+                tracked.stalled = false;
+            } else if let Some(timeout) = self.stall_timeout {
+                if !tracked.stalled && now.duration_since(tracked.last_moving) >= timeout {
+                    tracked.stalled = true;
+                    events.push(DownloadEvent::Stalled(s.gid));
+                }
+            }
+            tracked.completed = s.completed_len;
+        }
+
+        events
+    }
+}
+
+impl<U> Session<'_, U> {
+    /// Emit [`DownloadEvent::Progress`] events, at most once per `interval`, for the active
+    /// downloads that progressed. `None` disables them, which is the default.
+    ///
+    /// They are only emitted while the session is polled in once mode.
+    pub fn set_progress_interval(&mut self, interval: Option<Duration>) {
+        self.progress.interval = interval;
+    }
+
+    /// Emit a [`DownloadEvent::Stalled`] event when an active download didn't receive anything
+    /// for `timeout`. `None` disables it, which is the default.
+    pub fn set_stall_timeout(&mut self, timeout: Option<Duration>) {
+        self.progress.stall_timeout = timeout;
+    }
+
+    pub(crate) fn track_progress(&mut self) {
+        let now = Instant::now();
+        if !self.progress.is_due(now) {
+            return;
+        }
+
+        let active: Vec<Gid> = unsafe { ffi::get_active_download(self.handle) }
+            .into_iter()
+            .map(Gid::from)
+            .collect();
+        let stats = self.stats_for(&active);
+
+        let events = self.progress.update(now, &stats);
+        for event in events {
+            self.emit(event);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use crate::{errors::Result, options::Options};
+use libaria2_sys::ffi;
+use std::{convert::TryFrom, path::Path};
+
+/// Per-download options and position in the waiting queue, used when adding a download.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DownloadOptions {
+    options: Options,
+    position: Option<usize>,
+}
+
+impl DownloadOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Name of the downloaded file, relative to `dir`.
+    pub fn out(self, filename: impl Into<String>) -> Self {
+        self.map_options(|o| o.set("out", filename))
+    }
+
+    /// Directory to store the downloaded file, see [`Options::dir`].
+    pub fn dir(mut self, dir: impl AsRef<Path>) -> Result<Self> {
+        self.options = self.options.dir(dir)?;
+        Ok(self)
+    }
+
+    /// Append an HTTP header, like `"Authorization: Bearer ..."`.
+    pub fn header(self, header: impl Into<String>) -> Self {
+        self.map_options(|o| o.header(header))
+    }
+
+    /// Check the downloaded file with the given algorithm (like `sha-1` or `md5`) and hex digest.
+    pub fn checksum(self, algorithm: &str, digest: &str) -> Self {
+        self.map_options(|o| o.checksum(algorithm, digest))
+    }
+
+    /// Set any option accepted by aria2 for a single download, see the Input File section of
+    /// the aria2 manual.
+    pub fn option(self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.map_options(|o| o.set(key, value))
+    }
+
+    /// Add all of `options`, overriding the ones already set.
+    pub fn options(self, options: &Options) -> Self {
+        self.map_options(|o| o.merge(options))
+    }
+
+    /// Insert the download at this position in the waiting queue instead of appending it.
+    pub fn position(mut self, position: usize) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    fn map_options(mut self, f: impl FnOnce(Options) -> Options) -> Self {
+        self.options = f(self.options);
+        self
+    }
+
+    pub(crate) fn ffi_options(&self) -> Vec<ffi::KeyVal> {
+        self.options.to_ffi()
+    }
+
+    pub(crate) fn ffi_position(&self) -> i32 {
+        // A negative position appends to the queue, as does a position past its end.
+        self.position
+            .map(|p| i32::try_from(p).unwrap_or(i32::MAX))
+            .unwrap_or(-1)
+    }
+}
+
+impl AsRef<Options> for DownloadOptions {
+    fn as_ref(&self) -> &Options {
+        &self.options
+    }
+}
+
+impl From<Options> for DownloadOptions {
+    fn from(options: Options) -> Self {
+        Self {
+            options,
+            position: None,
+        }
+    }
+}
+
+/// A download from one or more URIs pointing to the same file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DownloadRequest {
+    uris: Vec<String>,
+    options: DownloadOptions,
+}
+
+impl DownloadRequest {
+    pub fn new(uri: impl Into<String>) -> Self {
+        Self {
+            uris: vec![uri.into()],
+            options: DownloadOptions::default(),
+        }
+    }
+
+    /// Add a mirror of the file, aria2 will use it when the other URIs fail or to split the
+    /// download between servers.
+    pub fn mirror(mut self, uri: impl Into<String>) -> Self {
+        self.uris.push(uri.into());
+        self
+    }
+
+    pub fn options(mut self, options: impl Into<DownloadOptions>) -> Self {
+        self.options = options.into();
+        self
+    }
+
+    pub fn uris(&self) -> &[String] {
+        &self.uris
+    }
+
+    pub(crate) fn download_options(&self) -> &DownloadOptions {
+        &self.options
+    }
+}
+
+impl From<&str> for DownloadRequest {
+    fn from(uri: &str) -> Self {
+        Self::new(uri)
+    }
+}
+
+impl From<String> for DownloadRequest {
+    fn from(uri: String) -> Self {
+        Self::new(uri)
+    }
+}
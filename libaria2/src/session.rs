@@ -1,29 +1,42 @@
 use crate::{
     errors::{AriaError, Result},
-    events::DownloadEvent,
+    events::{DownloadEvent, Listeners},
+    options::{catalog::OptionError, Options},
+    progress::ProgressTracker,
+    subscription::Subscribers,
     ARIA_STARTED,
 };
-use libaria2_sys::{ffi, A2Gid};
+use libaria2_sys::{ffi, A2Gid, EventCallback};
+use log::error;
+use once_cell::sync::OnceCell;
 use std::{
     collections::VecDeque,
-    sync::{
-        atomic::Ordering,
-        mpsc::{Receiver, Sender},
-    },
+    sync::{atomic::Ordering, mpsc::Receiver, Mutex},
+    time::Duration,
 };
 
-pub(crate) static mut EVENT_SENDER: Option<
-    Sender<(ffi::DownloadEvent, A2Gid, *mut std::ffi::c_void)>,
-> = None;
+/// Proof that aria2 is initialized, sessions borrow it so they can't outlive it.
+///
+/// [`Aria2Context::new`] and [`Aria2Context::global`] are the only ways to get one:
+///
+/// ```compile_fail
+/// let ctx = libaria2::session::Aria2Context;
+/// ```
+pub struct Aria2Context {
+    _private: (),
+}
 
-pub struct Aria2Context;
+pub(crate) type EventHandler<U> = Box<dyn FnMut(&mut U, &DownloadEvent)>;
 
 pub struct Session<'ctx, U> {
     pub(crate) handle: ffi::SessionHandle,
-    pub(crate) event_receiver: Receiver<(ffi::DownloadEvent, A2Gid, *mut std::ffi::c_void)>,
+    pub(crate) event_receiver: Receiver<(ffi::DownloadEvent, A2Gid)>,
     pub(crate) event_queue: VecDeque<DownloadEvent>,
+    pub(crate) listeners: Listeners<U>,
+    pub(crate) subscribers: Subscribers,
+    pub(crate) progress: ProgressTracker,
+    pub(crate) user_data: U,
     _ctx: std::marker::PhantomData<&'ctx ()>,
-    _user_data: std::marker::PhantomData<U>,
 }
 
 // TODO: When stabilized
@@ -31,54 +44,187 @@ pub struct Session<'ctx, U> {
 // impl<U> !Send for Session<'_, U> {}
 
 impl Aria2Context {
+    /// Initialize aria2.
+    ///
+    /// Only one context can be alive at a time, another call returns
+    /// [`AriaError::AlreadyInitialized`] until it is dropped, which deinitializes aria2.
     pub fn new() -> Result<Self> {
-        if ARIA_STARTED.load(Ordering::Relaxed) {
+        if ARIA_STARTED
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
             return Err(AriaError::AlreadyInitialized);
         }
-        ARIA_STARTED.store(false, Ordering::Release);
 
-        unsafe {
-            ffi::library_init();
+        let res = unsafe { ffi::library_init() };
+        if res != 0 {
+            ARIA_STARTED.store(false, Ordering::Release);
+            return Err(AriaError::InitError(res));
         }
 
-        Ok(Self)
+        Ok(Self { _private: () })
+    }
+
+    /// Context shared by the whole process, initialized on first use and never deinitialized.
+    ///
+    /// Fails with [`AriaError::AlreadyInitialized`] while a context created with
+    /// [`Aria2Context::new`] is alive.
+    pub fn global() -> Result<&'static Mutex<Aria2Context>> {
+        static GLOBAL: OnceCell<Mutex<Aria2Context>> = OnceCell::new();
+        GLOBAL.get_or_try_init(|| Aria2Context::new().map(Mutex::new))
     }
 
-    pub fn new_session(&mut self, keep_running: bool, options: &[(&str, &str)]) -> Session<()> {
-        let options = options
-            .iter()
-            .map(|(k, v)| ffi::KeyVal {
-                key: k.to_string(),
-                val: v.to_string(),
-            })
-            .collect();
+    pub fn new_session(&mut self, keep_running: bool, options: &Options) -> Result<Session<()>> {
+        SessionBuilder::new()
+            .keep_running(keep_running)
+            .options(options)
+            .build(self)
+    }
 
-        let (sender, receiver) = std::sync::mpsc::channel();
-        unsafe {
-            // It's safe because its only mutated here and only one Session can exist at any given time
-            // thanks to taking the context with &mut.
-            EVENT_SENDER = Some(sender);
+    /// Create a session that owns `data`, it is given to the event handlers along with each event.
+    pub fn new_session_with_data<U>(
+        &mut self,
+        data: U,
+        keep_running: bool,
+        options: &Options,
+    ) -> Result<Session<'_, U>> {
+        SessionBuilder::with_user_data(data)
+            .keep_running(keep_running)
+            .options(options)
+            .build(self)
+    }
+}
+
+/// Configuration of a session, see [`Aria2Context::new_session`] for the common case.
+pub struct SessionBuilder<U> {
+    options: Options,
+    keep_running: bool,
+    use_signal_handler: bool,
+    listeners: Listeners<U>,
+    progress_interval: Option<Duration>,
+    stall_timeout: Option<Duration>,
+    user_data: U,
+}
+
+impl SessionBuilder<()> {
+    pub fn new() -> Self {
+        Self::with_user_data(())
+    }
+}
+
+impl Default for SessionBuilder<()> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<U> SessionBuilder<U> {
+    /// Start building a session that owns `data`, see [`Aria2Context::new_session_with_data`].
+    pub fn with_user_data(data: U) -> Self {
+        Self {
+            options: Options::new(),
+            keep_running: false,
+            use_signal_handler: false,
+            listeners: Listeners::default(),
+            progress_interval: None,
+            stall_timeout: None,
+            user_data: data,
         }
+    }
 
-        let handle = unsafe {
-            ffi::session_new(
-                &options,
-                &ffi::SessionConfigFfi {
-                    keep_running,
-                    use_signal_handler: false,
-                    user_data: std::ptr::null() as *const std::ffi::c_void as usize,
-                },
-                Session::<()>::static_event_callback,
-            )
+    /// Keep the session running even when there is nothing to download, until it is shut down.
+    pub fn keep_running(mut self, keep_running: bool) -> Self {
+        self.keep_running = keep_running;
+        self
+    }
+
+    /// Let aria2 install its own handlers for SIGINT and SIGTERM, which shut the session down.
+    pub fn use_signal_handler(mut self, use_signal_handler: bool) -> Self {
+        self.use_signal_handler = use_signal_handler;
+        self
+    }
+
+    /// Add all of `options`, overriding the ones already set.
+    pub fn options(mut self, options: &Options) -> Self {
+        self.options = self.options.merge(options);
+        self
+    }
+
+    pub fn option(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.options.insert(key, value);
+        self
+    }
+
+    /// Same as [`Session::set_progress_interval`].
+    pub fn progress_interval(mut self, interval: Duration) -> Self {
+        self.progress_interval = Some(interval);
+        self
+    }
+
+    /// Same as [`Session::set_stall_timeout`].
+    pub fn stall_timeout(mut self, timeout: Duration) -> Self {
+        self.stall_timeout = Some(timeout);
+        self
+    }
+
+    /// Same as [`Session::on_event`].
+    pub fn on_event<F>(mut self, handler: F) -> Self
+    where
+        F: FnMut(&mut U, &DownloadEvent) + 'static,
+    {
+        self.listeners.add(None, None, Box::new(handler));
+        self
+    }
+
+    /// Create the session.
+    ///
+    /// The options known to the catalog are checked first, an invalid one is reported as
+    /// [`AriaError::SessionCreateError`]. aria2 doesn't tell which option it rejected, so if it
+    /// fails anyway the first option missing from the catalog is reported instead, and
+    /// [`AriaError::SessionCreateFailed`] if there is none.
+    pub fn build(self, _ctx: &mut Aria2Context) -> Result<Session<'_, U>> {
+        self.options.validate().map_err(|e| match e {
+            OptionError::InvalidValue { key, value, .. } => {
+                AriaError::SessionCreateError { key, value }
+            }
+            e => e.into(),
+        })?;
+
+        // Events are only forwarded by aria2 during `run`, they are processed in the next poll.
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let callback = EventCallback::new(move |_, event, gid| {
+            if let Err(e) = sender.send((event, gid)) {
+                error!("{}", e);
+            }
+            0
+        });
+
+        let config = ffi::SessionConfigFfi {
+            keep_running: self.keep_running,
+            use_signal_handler: self.use_signal_handler,
         };
+        let handle = unsafe { ffi::session_new(&self.options.to_ffi(), &config, callback) };
+        if !handle.is_valid() {
+            let options = &self.options;
+            return Err(options.unknown_keys().next().map_or(
+                AriaError::SessionCreateFailed,
+                |key| AriaError::SessionCreateError {
+                    key: key.to_string(),
+                    value: options.get(key).unwrap_or_default().to_string(),
+                },
+            ));
+        }
 
-        Session {
+        Ok(Session {
             handle,
             event_receiver: receiver,
             event_queue: Default::default(),
+            listeners: self.listeners,
+            subscribers: Subscribers::default(),
+            progress: ProgressTracker::new(self.progress_interval, self.stall_timeout),
+            user_data: self.user_data,
             _ctx: Default::default(),
-            _user_data: Default::default(),
-        }
+        })
     }
 }
 
@@ -95,7 +241,7 @@ pub struct PollContext<'a> {
 }
 
 impl PollContext<'_> {
-    fn new<U>(session: &Session<U>) -> Self {
+    pub(crate) fn new<U>(session: &Session<U>) -> Self {
         Self {
             handle: session.handle,
             _phantom: Default::default(),
@@ -104,15 +250,21 @@ impl PollContext<'_> {
 }
 
 impl<U> Session<'_, U> {
+    pub fn user_data(&self) -> &U {
+        &self.user_data
+    }
+
+    pub fn user_data_mut(&mut self) -> &mut U {
+        &mut self.user_data
+    }
+
     pub fn is_event_queue_empty(&self) -> bool {
         self.event_queue.is_empty()
     }
 
     pub fn poll(&mut self, mode_once: bool) -> Result<(RunResult, PollContext)> {
-        // Receive events stored in receiver.
-        while let Ok(event) = self.event_receiver.try_recv() {
-            self.handle_event(event);
-        }
+        self.drain_events();
+        self.track_progress();
 
         // Create a context for things that can only live for this poll round.
         let ctx = PollContext::new(self);
@@ -139,6 +291,13 @@ impl<U> Session<'_, U> {
         }
     }
 
+    /// Process the events sent by aria2 during the last run.
+    pub(crate) fn drain_events(&mut self) {
+        while let Ok(event) = self.event_receiver.try_recv() {
+            self.handle_event(event);
+        }
+    }
+
     pub fn shutdown(&mut self, force: bool) {
         // TODO: Real return value with enum of error codes
         unsafe {
@@ -160,5 +319,6 @@ impl Drop for Aria2Context {
         unsafe {
             ffi::library_deinit();
         }
+        ARIA_STARTED.store(false, Ordering::Release);
     }
 }
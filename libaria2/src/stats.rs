@@ -0,0 +1,81 @@
+use crate::{
+    download_handle::DownloadStatus, error_code::Aria2ErrorCode, gid::Gid, session::Session,
+};
+use libaria2_sys::{ffi, A2Gid};
+use std::convert::TryFrom;
+
+/// Aggregated statistics of all the downloads of a session.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GlobalStat {
+    /// Overall download speed in bytes/sec.
+    pub download_speed: u32,
+    /// Overall upload speed in bytes/sec.
+    pub upload_speed: u32,
+    pub num_active: u32,
+    pub num_waiting: u32,
+    pub num_stopped: u32,
+}
+
+impl From<ffi::GlobalStat> for GlobalStat {
+    fn from(stat: ffi::GlobalStat) -> Self {
+        // aria2 uses signed integers but none of these can be negative.
+        let unsigned = |v: i32| u32::try_from(v).unwrap_or_default();
+        Self {
+            download_speed: unsigned(stat.download_speed),
+            upload_speed: unsigned(stat.upload_speed),
+            num_active: unsigned(stat.num_active),
+            num_waiting: unsigned(stat.num_waiting),
+            num_stopped: unsigned(stat.num_stopped),
+        }
+    }
+}
+
+/// Progress of a single download, see [`Session::stats_for`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct DownloadStats {
+    pub gid: Gid,
+    pub status: DownloadStatus,
+    pub total_len: u64,
+    pub completed_len: u64,
+    pub upload_len: u64,
+    /// Download speed in bytes/sec.
+    pub download_speed: u32,
+    /// Upload speed in bytes/sec.
+    pub upload_speed: u32,
+    pub connections: u32,
+    pub error_code: Aria2ErrorCode,
+}
+
+impl From<ffi::DownloadStatsFfi> for DownloadStats {
+    fn from(stats: ffi::DownloadStatsFfi) -> Self {
+        Self {
+            gid: stats.gid.into(),
+            status: stats.status.into(),
+            total_len: u64::try_from(stats.total_len).unwrap_or_default(),
+            completed_len: u64::try_from(stats.completed_len).unwrap_or_default(),
+            upload_len: u64::try_from(stats.upload_len).unwrap_or_default(),
+            download_speed: u32::try_from(stats.download_speed).unwrap_or_default(),
+            upload_speed: u32::try_from(stats.upload_speed).unwrap_or_default(),
+            connections: u32::try_from(stats.connections).unwrap_or_default(),
+            error_code: stats.error_code.into(),
+        }
+    }
+}
+
+impl<U> Session<'_, U> {
+    pub fn global_stat(&self) -> GlobalStat {
+        unsafe { ffi::get_global_stat(self.handle) }.into()
+    }
+
+    /// Stats of many downloads at once, much cheaper than acquiring a handle for each of them.
+    ///
+    /// GIDs that don't match any download are skipped, the others keep their order.
+    pub fn stats_for(&self, gids: &[Gid]) -> Vec<DownloadStats> {
+        let gids: Vec<A2Gid> = gids.iter().map(Gid::raw).collect();
+        unsafe { ffi::get_download_stats(self.handle, &gids) }
+            .into_iter()
+            .map(DownloadStats::from)
+            .collect()
+    }
+}
@@ -0,0 +1,189 @@
+use log::warn;
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Condvar, Mutex},
+    task::Waker,
+};
+
+use crate::{events::DownloadEvent, session::Session};
+
+struct Buffer {
+    events: VecDeque<DownloadEvent>,
+    capacity: usize,
+    missed: u64,
+    overflowing: bool,
+    closed: bool,
+    waker: Option<Waker>,
+}
+
+struct Shared {
+    buffer: Mutex<Buffer>,
+    available: Condvar,
+}
+
+impl Shared {
+    fn update(&self, f: impl FnOnce(&mut Buffer)) {
+        let waker = {
+            let mut buffer = self.buffer.lock().unwrap();
+            f(&mut buffer);
+            buffer.waker.take()
+        };
+
+        self.available.notify_all();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// Receiving end of [`Session::subscribe`], every subscription gets its own copy of each event.
+///
+/// Events are only received while the session is polled, and the subscription ends when the
+/// session is dropped. If the consumer falls behind and the buffer is full, the oldest event is
+/// dropped and counted in [`missed`](Self::missed).
+///
+/// It can be consumed with [`blocking_iter`](Self::blocking_iter) or, with the `async` feature,
+/// as a `futures::Stream`.
+pub struct EventSubscription {
+    shared: Arc<Shared>,
+}
+
+impl EventSubscription {
+    /// Next event if one is already buffered, without blocking.
+    pub fn try_next(&mut self) -> Option<DownloadEvent> {
+        self.pop(&mut self.shared.buffer.lock().unwrap())
+    }
+
+    /// Block until an event is received, `None` once the session is dropped.
+    ///
+    /// Don't use it on the thread polling the session, it would wait forever.
+    pub fn recv(&mut self) -> Option<DownloadEvent> {
+        let mut buffer = self.shared.buffer.lock().unwrap();
+        loop {
+            if let Some(event) = self.pop(&mut buffer) {
+                return Some(event);
+            }
+            if buffer.closed {
+                return None;
+            }
+            buffer = self.shared.available.wait(buffer).unwrap();
+        }
+    }
+
+    /// Iterate over the events with [`recv`](Self::recv).
+    pub fn blocking_iter(&mut self) -> BlockingIter<'_> {
+        BlockingIter { subscription: self }
+    }
+
+    /// Number of events dropped because the buffer was full.
+    pub fn missed(&self) -> u64 {
+        self.shared.buffer.lock().unwrap().missed
+    }
+
+    /// Whether the session was dropped, buffered events can still be received.
+    pub fn is_closed(&self) -> bool {
+        self.shared.buffer.lock().unwrap().closed
+    }
+
+    fn pop(&self, buffer: &mut Buffer) -> Option<DownloadEvent> {
+        let event = buffer.events.pop_front();
+        if event.is_some() {
+            buffer.overflowing = false;
+        }
+        event
+    }
+}
+
+/// Blocking iterator over the events of a subscription, see
+/// [`EventSubscription::blocking_iter`].
+pub struct BlockingIter<'a> {
+    subscription: &'a mut EventSubscription,
+}
+
+impl Iterator for BlockingIter<'_> {
+    type Item = DownloadEvent;
+
+    fn next(&mut self) -> Option<DownloadEvent> {
+        self.subscription.recv()
+    }
+}
+
+#[cfg(feature = "async")]
+impl futures::Stream for EventSubscription {
+    type Item = DownloadEvent;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<DownloadEvent>> {
+        use std::task::Poll;
+
+        let mut buffer = self.shared.buffer.lock().unwrap();
+        if let Some(event) = self.pop(&mut buffer) {
+            Poll::Ready(Some(event))
+        } else if buffer.closed {
+            Poll::Ready(None)
+        } else {
+            buffer.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+/// Sending ends of the subscriptions of a session, they are closed when it is dropped.
+#[derive(Default)]
+pub(crate) struct Subscribers(Vec<Arc<Shared>>);
+
+impl Subscribers {
+    pub(crate) fn add(&mut self, capacity: usize) -> EventSubscription {
+        let shared = Arc::new(Shared {
+            buffer: Mutex::new(Buffer {
+                events: VecDeque::with_capacity(capacity),
+                capacity,
+                missed: 0,
+                overflowing: false,
+                closed: false,
+                waker: None,
+            }),
+            available: Condvar::new(),
+        });
+
+        self.0.push(shared.clone());
+        EventSubscription { shared }
+    }
+
+    pub(crate) fn publish(&mut self, event: &DownloadEvent) {
+        // Forget the subscriptions that were dropped.
+        self.0.retain(|shared| Arc::strong_count(shared) > 1);
+
+        for shared in &self.0 {
+            shared.update(|buffer| {
+                if buffer.events.len() >= buffer.capacity {
+                    buffer.events.pop_front();
+                    buffer.missed += 1;
+                    if !buffer.overflowing {
+                        warn!("An event subscription is full, dropping its oldest events");
+                        buffer.overflowing = true;
+                    }
+                }
+                buffer.events.push_back(*event);
+            });
+        }
+    }
+}
+
+impl Drop for Subscribers {
+    fn drop(&mut self) {
+        for shared in &self.0 {
+            shared.update(|buffer| buffer.closed = true);
+        }
+    }
+}
+
+impl<U> Session<'_, U> {
+    /// Receive a copy of every event of the session from now on, buffering up to `capacity`
+    /// of them. A `capacity` of 0 is treated as 1, there must be room for the latest event.
+    pub fn subscribe(&mut self, capacity: usize) -> EventSubscription {
+        self.subscribers.add(capacity.max(1))
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{
+    download_handle::DownloadStatus,
+    error_code::Aria2ErrorCode,
+    errors::{AriaError, Result},
+    gid::Gid,
+    session::{PollContext, RunResult, Session},
+};
+
+/// How a download ended, see [`Session::run_until_finished`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DownloadOutcome {
+    /// The download and every download following it completed.
+    Completed,
+    /// The download or one of its followers failed.
+    Failed(Aria2ErrorCode),
+    /// The download or one of its followers was removed.
+    Removed,
+}
+
+impl<U> Session<'_, U> {
+    /// Poll the session until `gid` is finished.
+    ///
+    /// A download that is followed by others, like a .torrent or metalink file downloaded over
+    /// HTTP, is only finished once the downloads it created are. A torrent is finished as soon
+    /// as it is seeding.
+    pub fn run_until_finished(&mut self, gid: Gid) -> Result<DownloadOutcome> {
+        let mut pending = vec![gid];
+        if let Some(outcome) = self.settle(&mut pending)? {
+            return Ok(outcome);
+        }
+        loop {
+            if let Some(outcome) = self.wait_step(&mut pending)? {
+                return Ok(outcome);
+            }
+        }
+    }
+
+    /// Same as [`run_until_finished`](Self::run_until_finished) but yields to the executor
+    /// between each round of aria2's event loop.
+    ///
+    /// The future drives the session itself, so each poll blocks the thread while aria2 runs.
+    /// Use `AsyncSessionHandle::wait_for` to wait from a multi-threaded service.
+    #[cfg(feature = "async")]
+    pub async fn wait_for(&mut self, gid: Gid) -> Result<DownloadOutcome> {
+        let mut pending = vec![gid];
+        if let Some(outcome) = self.settle(&mut pending)? {
+            return Ok(outcome);
+        }
+        loop {
+            if let Some(outcome) = self.wait_step(&mut pending)? {
+                return Ok(outcome);
+            }
+            YieldNow(false).await;
+        }
+    }
+
+    /// Run aria2 once and check `pending` again if it concerns one of them.
+    fn wait_step(&mut self, pending: &mut Vec<Gid>) -> Result<Option<DownloadOutcome>> {
+        let (res, _) = self.poll(true)?;
+        match res {
+            RunResult::Event(event) if pending.contains(&event.gid()) => self.settle(pending),
+            RunResult::Event(_) | RunResult::Continue => Ok(None),
+            RunResult::Done => {
+                // Nothing is left to run, if a download didn't finish it never will.
+                if let Some(outcome) = self.settle(pending)? {
+                    return Ok(Some(outcome));
+                }
+                let gid = pending[0];
+                Err(AriaError::InvalidDownloadState {
+                    action: "wait for",
+                    gid,
+                    status: self
+                        .download_status(gid)
+                        .ok_or(AriaError::UnknownGid(gid))?,
+                })
+            }
+        }
+    }
+
+    /// Replace the completed downloads of `pending` by the ones following them, and return the
+    /// outcome once every download is finished or one of them failed.
+    pub(crate) fn settle(&self, pending: &mut Vec<Gid>) -> Result<Option<DownloadOutcome>> {
+        let ctx = PollContext::new(self);
+        let mut i = 0;
+        while i < pending.len() {
+            let gid = pending[i];
+            let handle = ctx.acquire_handle(gid).ok_or(AriaError::UnknownGid(gid))?;
+
+            match handle.status() {
+                DownloadStatus::Error => {
+                    return Ok(Some(DownloadOutcome::Failed(handle.error_code())));
+                }
+                DownloadStatus::Removed => return Ok(Some(DownloadOutcome::Removed)),
+                DownloadStatus::Active if handle.is_seeding() => {
+                    pending.swap_remove(i);
+                }
+                DownloadStatus::Complete => {
+                    pending.swap_remove(i);
+                    for child in handle.followed_by() {
+                        if !pending.contains(&child) {
+                            pending.push(child);
+                        }
+                    }
+                }
+                DownloadStatus::Active | DownloadStatus::Waiting | DownloadStatus::Paused => {
+                    i += 1;
+                }
+            }
+        }
+
+        if pending.is_empty() {
+            Ok(Some(DownloadOutcome::Completed))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Future that is ready the second time it is polled.
+#[cfg(feature = "async")]
+struct YieldNow(bool);
+
+#[cfg(feature = "async")]
+impl std::future::Future for YieldNow {
+    type Output = ();
+
+    fn poll(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<()> {
+        if self.0 {
+            std::task::Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            std::task::Poll::Pending
+        }
+    }
+}
@@ -0,0 +1,206 @@
+#![cfg(feature = "async")]
+
+use futures::{executor::block_on, StreamExt};
+use libaria2::{errors::AriaError, events::DownloadEvent, prelude::*};
+use nix::{sys::wait::WaitStatus, unistd::ForkResult};
+
+pub fn test_harness(test: fn()) {
+    // Skip harness if env variable say so
+    if let Some(opt) = option_env!("NO_HARNESS") {
+        if opt != "0" && opt.to_lowercase() != "false" {
+            test();
+            return;
+        }
+    }
+
+    // Can't setup harness if not in a unix environment
+    if cfg!(not(unix)) {
+        println!("== WARNING: non-unix OS aren't supported by the test harness !");
+        println!("== Running the test anyway but expect weird errors");
+        test();
+        return;
+    }
+
+    let fork_res = unsafe { nix::unistd::fork().unwrap() };
+
+    if fork_res.is_child() {
+        let res = std::panic::catch_unwind(test);
+
+        if let Err(e) = res {
+            eprintln!("{:?}", e);
+            std::process::exit(1);
+        } else {
+            std::process::exit(0);
+        }
+    } else if let ForkResult::Parent { child } = fork_res {
+        let res = nix::sys::wait::waitpid(child, None).unwrap();
+        if let WaitStatus::Exited(_, 0) = res {
+            // Ok
+        } else {
+            panic!("Child failed !");
+        }
+    }
+}
+
+fn spawn() -> AsyncSession {
+    let options = Options::new()
+        .dir(std::env::temp_dir())
+        .unwrap()
+        .set("no-conf", "true")
+        .set("max-overall-download-limit", "1");
+    AsyncSession::spawn(Aria2Context::new().unwrap(), options).unwrap()
+}
+
+#[test]
+fn handle_is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync + Clone>() {}
+    assert_send_sync::<AsyncSessionHandle>();
+
+    // The futures must be usable from multi-threaded executors too.
+    fn assert_send<T: Send>(_: &T) {}
+    fn check_futures(handle: &AsyncSessionHandle) {
+        assert_send(&handle.add_uri(DownloadRequest::new("http://localhost/1")));
+        assert_send(&handle.snapshot(Gid::NULL));
+    }
+    let _ = check_futures;
+}
+
+#[test]
+fn control_downloads() {
+    test_harness(|| {
+        let session = spawn();
+        let handle = session.handle();
+
+        block_on(async {
+            let gid = handle
+                .add_uri(DownloadRequest::new("http://localhost/1"))
+                .await
+                .unwrap();
+            assert!(handle.snapshot(gid).await.unwrap().is_some());
+            assert_eq!(handle.stats_for(vec![gid]).await.unwrap().len(), 1);
+
+            handle.remove(gid, true).await.unwrap();
+            assert!(matches!(
+                handle.pause(Gid::new(1), false).await,
+                Err(AriaError::UnknownGid(_))
+            ));
+        });
+
+        session.shutdown(true);
+        assert!(matches!(
+            block_on(handle.global_stat()),
+            Err(AriaError::SessionClosed)
+        ));
+    });
+}
+
+#[test]
+fn handles_from_many_threads() {
+    test_harness(|| {
+        let session = spawn();
+
+        let threads: Vec<_> = (0..4)
+            .map(|i| {
+                let handle = session.handle();
+                std::thread::spawn(move || {
+                    let request = DownloadRequest::new(format!("http://localhost/{}", i));
+                    block_on(handle.add_uri(request)).unwrap()
+                })
+            })
+            .collect();
+        let gids: Vec<_> = threads.into_iter().map(|t| t.join().unwrap()).collect();
+
+        let stats = block_on(session.handle().stats_for(gids)).unwrap();
+        assert_eq!(stats.len(), 4);
+    });
+}
+
+#[test]
+fn event_stream() {
+    test_harness(|| {
+        let session = spawn();
+        let handle = session.handle();
+
+        block_on(async {
+            let mut events = handle.subscribe(16).await.unwrap();
+            let gid = handle
+                .add_uri(DownloadRequest::new("http://localhost/1"))
+                .await
+                .unwrap();
+            assert_eq!(events.next().await, Some(DownloadEvent::Added(gid)));
+            assert_eq!(events.next().await, Some(DownloadEvent::Started(gid)));
+
+            drop(session);
+            while events.next().await.is_some() {}
+            assert!(events.is_closed());
+        });
+    });
+}
+
+#[test]
+fn wait_for() {
+    test_harness(|| {
+        let session = spawn();
+        let handle = session.handle();
+
+        block_on(async {
+            let gid = handle
+                .add_uri(DownloadRequest::new("http://localhost/1"))
+                .await
+                .unwrap();
+            handle.remove(gid, true).await.unwrap();
+            assert_eq!(
+                handle.wait_for(gid).await.unwrap(),
+                DownloadOutcome::Removed
+            );
+        });
+    });
+}
+
+#[test]
+fn add_after_finished() {
+    test_harness(|| {
+        let session = spawn();
+        let handle = session.handle();
+
+        block_on(async {
+            let first = handle
+                .add_uri(DownloadRequest::new("http://localhost/1"))
+                .await
+                .unwrap();
+            assert!(matches!(
+                handle.wait_for(first).await.unwrap(),
+                DownloadOutcome::Failed(_)
+            ));
+
+            // The session is still running once every download is finished.
+            let second = handle
+                .add_uri(DownloadRequest::new("http://localhost/2"))
+                .await
+                .unwrap();
+            assert!(matches!(
+                handle.wait_for(second).await.unwrap(),
+                DownloadOutcome::Failed(_)
+            ));
+            assert_eq!(handle.global_stat().await.unwrap().num_stopped, 2);
+        });
+    });
+}
+
+#[test]
+fn zero_capacity_subscription() {
+    test_harness(|| {
+        let session = spawn();
+        let handle = session.handle();
+
+        block_on(async {
+            let mut events = handle.subscribe(0).await.unwrap();
+            // The session thread is still serving commands.
+            let gid = handle
+                .add_uri(DownloadRequest::new("http://localhost/1"))
+                .await
+                .unwrap();
+            assert_eq!(events.next().await, Some(DownloadEvent::Added(gid)));
+        });
+    });
+}
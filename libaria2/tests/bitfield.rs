@@ -0,0 +1,36 @@
+use libaria2::bitfield::PieceBitfield;
+
+#[test]
+fn decode() {
+    // 10 pieces of 100 bytes, the last one is 50 bytes long.
+    let pieces = PieceBitfield::from_hex("b3c0", 10, 100, 950).unwrap();
+    let expected = [
+        true, false, true, true, false, false, true, true, true, true,
+    ];
+    assert_eq!(pieces.iter().collect::<Vec<_>>(), expected);
+    assert!(!pieces.has_piece(10));
+    assert_eq!(pieces.completed_count(), 7);
+    assert_eq!(pieces.first_missing(), Some(1));
+    assert!(!pieces.is_complete());
+
+    assert!(PieceBitfield::from_hex("b3", 10, 100, 950).is_none());
+    assert!(PieceBitfield::from_hex("b3c", 10, 100, 950).is_none());
+    assert!(PieceBitfield::from_hex("b3cz", 10, 100, 950).is_none());
+}
+
+#[test]
+fn ranges() {
+    let pieces = PieceBitfield::from_hex("b3c0", 10, 100, 950).unwrap();
+    assert_eq!(pieces.completed_ranges(), vec![0..100, 200..400, 600..950]);
+    assert_eq!(pieces.piece_range(9), 900..950);
+    assert_eq!(pieces.completed_prefix_len(), 100);
+
+    let full = PieceBitfield::from_hex("ffc0", 10, 100, 950).unwrap();
+    assert!(full.is_complete());
+    assert_eq!(full.completed_ranges(), vec![0..950]);
+    assert_eq!(full.completed_prefix_len(), 950);
+
+    let empty = PieceBitfield::from_hex("", 0, 0, 0).unwrap();
+    assert!(empty.completed_ranges().is_empty());
+    assert_eq!(empty.first_missing(), None);
+}
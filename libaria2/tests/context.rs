@@ -0,0 +1,530 @@
+use libaria2::{
+    download_handle::DownloadStatus,
+    errors::AriaError,
+    events::{DownloadEvent, EventKind},
+    prelude::*,
+    session::RunResult,
+};
+use nix::{sys::wait::WaitStatus, unistd::ForkResult};
+
+pub fn test_harness(test: fn()) {
+    // Skip harness if env variable say so
+    if let Some(opt) = option_env!("NO_HARNESS") {
+        if opt != "0" && opt.to_lowercase() != "false" {
+            test();
+            return;
+        }
+    }
+
+    // Can't setup harness if not in a unix environment
+    if cfg!(not(unix)) {
+        println!("== WARNING: non-unix OS aren't supported by the test harness !");
+        println!("== Running the test anyway but expect weird errors");
+        test();
+        return;
+    }
+
+    let fork_res = unsafe { nix::unistd::fork().unwrap() };
+
+    if fork_res.is_child() {
+        let res = std::panic::catch_unwind(test);
+
+        if let Err(e) = res {
+            eprintln!("{:?}", e);
+            std::process::exit(1);
+        } else {
+            std::process::exit(0);
+        }
+    } else if let ForkResult::Parent { child } = fork_res {
+        let res = nix::sys::wait::waitpid(child, None).unwrap();
+        if let WaitStatus::Exited(_, 0) = res {
+            // Ok
+        } else {
+            panic!("Child failed !");
+        }
+    }
+}
+
+fn test_options() -> Options {
+    Options::new()
+        .dir(std::env::temp_dir())
+        .unwrap()
+        .set("no-conf", "true")
+}
+
+#[test]
+fn init_once_at_a_time() {
+    test_harness(|| {
+        let ctx = Aria2Context::new().unwrap();
+        assert!(matches!(
+            Aria2Context::new(),
+            Err(AriaError::AlreadyInitialized)
+        ));
+
+        // aria2 can be initialized again once the context is dropped.
+        drop(ctx);
+        let mut ctx = Aria2Context::new().unwrap();
+        let session = ctx.new_session(false, &test_options()).unwrap();
+        drop(session);
+    });
+}
+
+#[test]
+fn global_context() {
+    test_harness(|| {
+        let first = Aria2Context::global().unwrap();
+        let second = Aria2Context::global().unwrap();
+        assert!(std::ptr::eq(first, second));
+
+        assert!(matches!(
+            Aria2Context::new(),
+            Err(AriaError::AlreadyInitialized)
+        ));
+
+        let mut ctx = first.lock().unwrap();
+        let session = ctx.new_session(false, &test_options()).unwrap();
+        drop(session);
+    });
+}
+
+#[test]
+fn global_after_new() {
+    test_harness(|| {
+        let _ctx = Aria2Context::new().unwrap();
+        assert!(matches!(
+            Aria2Context::global(),
+            Err(AriaError::AlreadyInitialized)
+        ));
+    });
+}
+
+#[test]
+fn session_create_error() {
+    test_harness(|| {
+        let mut ctx = Aria2Context::new().unwrap();
+
+        let err = SessionBuilder::new()
+            .options(&test_options())
+            .option("checksum", "not-a-checksum")
+            .build(&mut ctx)
+            .err();
+        match err {
+            Some(AriaError::SessionCreateError { key, value }) => {
+                assert_eq!(key, "checksum");
+                assert_eq!(value, "not-a-checksum");
+            }
+            _ => panic!("The session shouldn't have been created"),
+        }
+
+        // aria2 rejects this option but the catalog doesn't know it, it is still reported.
+        let err = SessionBuilder::new()
+            .options(&test_options())
+            .option("enable-async-dns6", "maybe")
+            .build(&mut ctx)
+            .err();
+        match err {
+            Some(AriaError::SessionCreateError { key, value }) => {
+                assert_eq!(key, "enable-async-dns6");
+                assert_eq!(value, "maybe");
+            }
+            _ => panic!("The session shouldn't have been created"),
+        }
+
+        // A failed session doesn't prevent creating another one.
+        let session = SessionBuilder::new()
+            .options(&test_options())
+            .use_signal_handler(false)
+            .build(&mut ctx);
+        assert!(session.is_ok());
+    });
+}
+
+#[test]
+fn add_uri_request() {
+    test_harness(|| {
+        let mut ctx = Aria2Context::new().unwrap();
+        let mut session = ctx.new_session(false, &test_options()).unwrap();
+        let paused = DownloadOptions::new().option("pause", "true");
+
+        let gid = session
+            .add_uri(
+                &DownloadRequest::new("http://localhost/1")
+                    .mirror("http://127.0.0.1/1")
+                    .options(paused.clone().out("file")),
+            )
+            .unwrap();
+        let info = session.snapshot(gid).unwrap();
+        let uris: Vec<_> = info.files()[0].uris().iter().map(|u| u.uri()).collect();
+        assert_eq!(uris, ["http://localhost/1", "http://127.0.0.1/1"]);
+        assert_eq!(info.options().get("out"), Some("file"));
+
+        session
+            .add_uri(&DownloadRequest::new("http://localhost/2").options(paused.clone()))
+            .unwrap();
+        let first = session
+            .add_uri(&DownloadRequest::new("http://localhost/3").options(paused.position(0)))
+            .unwrap();
+        assert_eq!(session.move_to(first, Position::Current(0)).unwrap(), 0);
+        assert_eq!(session.move_to(gid, Position::Current(0)).unwrap(), 1);
+    });
+}
+
+#[test]
+fn download_control_errors() {
+    test_harness(|| {
+        let mut ctx = Aria2Context::new().unwrap();
+        let mut session = ctx.new_session(false, &test_options()).unwrap();
+
+        let unknown = Gid::new(1);
+        assert!(matches!(
+            session.pause(unknown, false),
+            Err(AriaError::UnknownGid(g)) if g == unknown
+        ));
+        assert!(matches!(
+            session.move_to(unknown, Position::Start(0)),
+            Err(AriaError::UnknownGid(_))
+        ));
+
+        // A server that never answers keeps the download active.
+        let server = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let uri = format!("http://{}/file", server.local_addr().unwrap());
+        let gid = session.add_uri(&DownloadRequest::new(uri)).unwrap();
+        while session.snapshot(gid).unwrap().status() != DownloadStatus::Active {
+            session.poll(true).unwrap();
+        }
+
+        assert!(matches!(
+            session.unpause(gid),
+            Err(AriaError::InvalidDownloadState {
+                action: "unpause",
+                gid: g,
+                status: DownloadStatus::Active,
+            }) if g == gid
+        ));
+        assert!(matches!(
+            session.move_to(gid, Position::Start(0)),
+            Err(AriaError::InvalidDownloadState { action: "move", .. })
+        ));
+    });
+}
+
+#[test]
+fn move_in_queue() {
+    test_harness(|| {
+        let mut ctx = Aria2Context::new().unwrap();
+        let mut session = ctx.new_session(false, &test_options()).unwrap();
+
+        let paused = DownloadOptions::new().option("pause", "true");
+        let gids: Vec<_> = (0..3)
+            .map(|i| {
+                let request =
+                    DownloadRequest::new(format!("http://localhost/{}", i)).options(paused.clone());
+                session.add_uri(&request).unwrap()
+            })
+            .collect();
+
+        assert_eq!(session.move_to(gids[2], Position::Start(0)).unwrap(), 0);
+        assert_eq!(session.move_to(gids[2], Position::Current(1)).unwrap(), 1);
+        assert_eq!(session.move_to(gids[0], Position::End(0)).unwrap(), 2);
+        assert_eq!(session.move_to(gids[0], Position::Current(-1)).unwrap(), 1);
+        // Out of range positions are clamped to the queue.
+        assert_eq!(session.move_to(gids[1], Position::Start(10)).unwrap(), 2);
+    });
+}
+
+#[test]
+fn global_stat() {
+    test_harness(|| {
+        let mut ctx = Aria2Context::new().unwrap();
+        let mut session = ctx.new_session(false, &test_options()).unwrap();
+        assert_eq!(session.global_stat(), GlobalStat::default());
+
+        let paused = DownloadOptions::new().option("pause", "true");
+        for i in 0..2 {
+            let request =
+                DownloadRequest::new(format!("http://localhost/{}", i)).options(paused.clone());
+            session.add_uri(&request).unwrap();
+        }
+
+        let stat = session.global_stat();
+        assert_eq!(stat.num_active, 0);
+        assert_eq!(stat.num_waiting, 2);
+        assert_eq!(stat.num_stopped, 0);
+        assert_eq!(stat.download_speed, 0);
+    });
+}
+
+#[test]
+fn snapshot_matches_handle() {
+    fn assert_owned<T: Send + Sync + Clone + 'static>() {}
+    assert_owned::<DownloadInfo>();
+
+    test_harness(|| {
+        let mut ctx = Aria2Context::new().unwrap();
+        let mut session = ctx.new_session(false, &test_options()).unwrap();
+
+        let gid = session
+            .add_torrent(
+                std::path::Path::new("../libaria2-sys/tests/test.torrent"),
+                &DownloadOptions::new().option("pause", "true"),
+            )
+            .unwrap();
+        let info = session.snapshot(gid).unwrap();
+        assert!(session.snapshot(Gid::new(1)).is_none());
+
+        // Queued events are returned without running aria2, so nothing changed since.
+        let (_, poll_ctx) = session.poll(true).unwrap();
+        let handle = poll_ctx.acquire_handle(gid).unwrap();
+        assert_eq!(info.gid(), gid);
+        assert_eq!(info.status(), handle.status());
+        assert_eq!(info.total_len(), handle.total_len() as u64);
+        assert_eq!(info.completed_len(), handle.completed_len() as u64);
+        assert_eq!(info.info_hash(), handle.info_hash());
+        assert_eq!(info.dir(), handle.dir());
+        assert_eq!(info.files(), handle.files());
+        assert_eq!(info.options(), &handle.options());
+        assert_eq!(info.bt_meta_info(), handle.bt_meta_info().as_ref());
+        assert!(info.info_hash().is_some());
+
+        // The snapshot outlives the poll round and can be sent to another thread.
+        let copy = info.clone();
+        let total = std::thread::spawn(move || copy.total_len()).join().unwrap();
+        assert_eq!(total, info.total_len());
+    });
+}
+
+#[test]
+fn builder_keep_running() {
+    test_harness(|| {
+        let mut ctx = Aria2Context::new().unwrap();
+        let mut session = SessionBuilder::with_user_data(Vec::new())
+            .keep_running(true)
+            .options(&test_options())
+            .on_event(|events: &mut Vec<DownloadEvent>, event| events.push(*event))
+            .build(&mut ctx)
+            .unwrap();
+
+        let gid = session
+            .add_uri(
+                &DownloadRequest::new("http://localhost/1")
+                    .options(DownloadOptions::new().option("pause", "true")),
+            )
+            .unwrap();
+        assert_eq!(session.user_data(), &[DownloadEvent::Added(gid)]);
+
+        // Nothing is left to download but the session keeps running.
+        while !session.is_event_queue_empty() {
+            session.poll(true).unwrap();
+        }
+        for _ in 0..3 {
+            let (res, _) = session.poll(true).unwrap();
+            assert_ne!(res, RunResult::Done);
+        }
+
+        session.shutdown(true);
+        while session.poll(true).unwrap().0 != RunResult::Done {}
+    });
+}
+
+#[test]
+fn download_files() {
+    test_harness(|| {
+        let mut ctx = Aria2Context::new().unwrap();
+        let mut session = ctx.new_session(false, &test_options()).unwrap();
+
+        let paused = DownloadOptions::new().option("pause", "true");
+        let torrent = session
+            .add_torrent(
+                std::path::Path::new("../libaria2-sys/tests/test.torrent"),
+                &paused,
+            )
+            .unwrap();
+        let uri = session
+            .add_uri(&DownloadRequest::new("http://localhost/1").options(paused))
+            .unwrap();
+
+        // Queued events are returned without running aria2.
+        let (_, poll_ctx) = session.poll(true).unwrap();
+
+        let handle = poll_ctx.acquire_handle(torrent).unwrap();
+        let files = handle.files();
+        assert_eq!(files.len(), handle.num_files() as usize);
+        assert!(handle.get_file(0).is_none());
+        assert!(handle.get_file(handle.num_files() + 1).is_none());
+
+        let first = handle.get_file(1).unwrap();
+        assert_eq!(first.index(), 1);
+        assert_eq!(first.path(), files[0].path());
+        assert_eq!(first.len(), files[0].len());
+        assert!(!first.is_empty());
+        assert!(!first.is_completed());
+
+        // The size of an HTTP download is unknown until the server answers.
+        let handle = poll_ctx.acquire_handle(uri).unwrap();
+        let file = handle.get_file(1).unwrap();
+        assert_eq!(file.len(), 0);
+        assert!(!file.is_completed());
+    });
+}
+
+#[test]
+fn event_subscriptions() {
+    test_harness(|| {
+        let mut ctx = Aria2Context::new().unwrap();
+        let mut session = ctx
+            .new_session(false, &test_options().set("max-tries", "1"))
+            .unwrap();
+
+        let mut all = session.subscribe(16);
+        let mut last = session.subscribe(1);
+        let mut zero = session.subscribe(0);
+        let gid = session
+            .add_uri(&DownloadRequest::new("http://localhost/1"))
+            .unwrap();
+
+        while let Ok((res, _)) = session.poll(false) {
+            if res == RunResult::Done {
+                break;
+            }
+        }
+
+        // The download can't succeed, only the error remains in the smallest buffer.
+        assert!(matches!(last.try_next(), Some(DownloadEvent::Error(g, _)) if g == gid));
+        assert!(last.try_next().is_none());
+        assert!(last.missed() >= 1);
+        assert!(matches!(zero.try_next(), Some(DownloadEvent::Error(g, _)) if g == gid));
+
+        drop(session);
+        assert!(last.is_closed());
+        let events: Vec<_> = all.blocking_iter().collect();
+        assert_eq!(
+            events[..2],
+            [DownloadEvent::Added(gid), DownloadEvent::Started(gid)]
+        );
+        assert!(matches!(events.last(), Some(DownloadEvent::Error(g, _)) if *g == gid));
+    });
+}
+
+#[test]
+fn run_until_finished() {
+    test_harness(|| {
+        let mut ctx = Aria2Context::new().unwrap();
+        let mut session = ctx
+            .new_session(false, &test_options().set("max-tries", "1"))
+            .unwrap();
+
+        let failing = session
+            .add_uri(&DownloadRequest::new("http://localhost/1"))
+            .unwrap();
+        assert!(matches!(
+            session.run_until_finished(failing),
+            Ok(DownloadOutcome::Failed(_))
+        ));
+        // Finished downloads resolve right away.
+        assert!(matches!(
+            session.run_until_finished(failing),
+            Ok(DownloadOutcome::Failed(_))
+        ));
+
+        let removed = session
+            .add_uri(&DownloadRequest::new("http://localhost/2"))
+            .unwrap();
+        session.remove(removed, true).unwrap();
+        assert_eq!(
+            session.run_until_finished(removed).unwrap(),
+            DownloadOutcome::Removed
+        );
+
+        assert!(matches!(
+            session.run_until_finished(Gid::new(1)),
+            Err(AriaError::UnknownGid(_))
+        ));
+    });
+}
+
+#[derive(Default)]
+struct Counters {
+    first_errors: u32,
+    any_errors: u32,
+    second_starts: u32,
+}
+
+#[test]
+fn filtered_listeners() {
+    test_harness(|| {
+        let mut ctx = Aria2Context::new().unwrap();
+        let mut session = ctx
+            .new_session_with_data(
+                Counters::default(),
+                false,
+                &test_options().set("max-tries", "1"),
+            )
+            .unwrap();
+
+        let first = session
+            .add_uri(&DownloadRequest::new("http://localhost/1"))
+            .unwrap();
+        let second = session
+            .add_uri(&DownloadRequest::new("http://localhost/2"))
+            .unwrap();
+
+        session.on(first, EventKind::Error, |c: &mut Counters, _| {
+            c.first_errors += 1
+        });
+        session.on(None, EventKind::Error, |c: &mut Counters, _| {
+            c.any_errors += 1
+        });
+        let starts = session.on(second, EventKind::Start, |c: &mut Counters, _| {
+            c.second_starts += 1
+        });
+        session.remove_listener(starts).unwrap();
+        assert!(matches!(
+            session.remove_listener(starts),
+            Err(AriaError::InvalidCallbackHandle(_))
+        ));
+
+        session.run_until_finished(first).unwrap();
+        session.run_until_finished(second).unwrap();
+
+        let counters = session.user_data();
+        assert_eq!(counters.first_errors, 1);
+        assert_eq!(counters.any_errors, 2);
+        assert_eq!(counters.second_starts, 0);
+    });
+}
+
+#[test]
+fn synthesized_events() {
+    test_harness(|| {
+        let mut ctx = Aria2Context::new().unwrap();
+        let mut session = SessionBuilder::new()
+            .options(&test_options())
+            .progress_interval(std::time::Duration::from_millis(100))
+            .stall_timeout(std::time::Duration::from_secs(60))
+            .build(&mut ctx)
+            .unwrap();
+
+        let mut events = session.subscribe(16);
+        let gid = session
+            .add_uri(&DownloadRequest::new("http://localhost/1"))
+            .unwrap();
+        session.remove(gid, true).unwrap();
+
+        assert_eq!(events.try_next(), Some(DownloadEvent::Added(gid)));
+        assert_eq!(events.try_next(), Some(DownloadEvent::Removed(gid)));
+        assert_eq!(DownloadEvent::Removed(gid).kind(), EventKind::Remove);
+
+        // Nothing was downloaded, so there is no progress to report.
+        while let Ok((res, _)) = session.poll(true) {
+            if res == RunResult::Done {
+                break;
+            }
+        }
+        while let Some(event) = events.try_next() {
+            assert!(!matches!(
+                event,
+                DownloadEvent::Progress { .. } | DownloadEvent::Stalled(_)
+            ));
+        }
+    });
+}
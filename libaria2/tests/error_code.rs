@@ -0,0 +1,49 @@
+use libaria2::error_code::Aria2ErrorCode;
+
+#[test]
+fn from_code() {
+    assert_eq!(Aria2ErrorCode::from(0), Aria2ErrorCode::Finished);
+    assert_eq!(Aria2ErrorCode::from(3), Aria2ErrorCode::ResourceNotFound);
+    assert_eq!(Aria2ErrorCode::from(32), Aria2ErrorCode::ChecksumError);
+    assert_eq!(Aria2ErrorCode::from(33), Aria2ErrorCode::Other(33));
+    assert_eq!(Aria2ErrorCode::from(-1), Aria2ErrorCode::Other(-1));
+
+    for code in -1..=33 {
+        assert_eq!(i32::from(Aria2ErrorCode::from(code)), code);
+    }
+    assert!(Aria2ErrorCode::Finished.is_success());
+    assert!(!Aria2ErrorCode::UnknownError.is_success());
+}
+
+#[test]
+fn display() {
+    assert_eq!(
+        Aria2ErrorCode::NetworkProblem.to_string(),
+        "a network problem occurred"
+    );
+    assert_eq!(
+        Aria2ErrorCode::Other(42).to_string(),
+        "unrecognized error code 42"
+    );
+    for code in 0..=32 {
+        assert!(!matches!(
+            Aria2ErrorCode::from(code),
+            Aria2ErrorCode::Other(_)
+        ));
+        assert!(!Aria2ErrorCode::from(code)
+            .to_string()
+            .starts_with("unrecognized"));
+    }
+}
+
+#[test]
+fn retryable() {
+    assert!(Aria2ErrorCode::TimeOut.is_retryable());
+    assert!(Aria2ErrorCode::NetworkProblem.is_retryable());
+    assert!(Aria2ErrorCode::HttpServiceUnavailable.is_retryable());
+
+    assert!(!Aria2ErrorCode::Finished.is_retryable());
+    assert!(!Aria2ErrorCode::ResourceNotFound.is_retryable());
+    assert!(!Aria2ErrorCode::ChecksumError.is_retryable());
+    assert!(!Aria2ErrorCode::Other(-1).is_retryable());
+}
@@ -0,0 +1,50 @@
+use libaria2::gid::{Gid, GidParseError};
+use std::collections::BTreeSet;
+
+#[test]
+fn display_and_parse() {
+    let gid = Gid::new(0x2089b05ecca3d829);
+    assert_eq!(gid.to_string(), "2089b05ecca3d829");
+    assert_eq!(format!("{:?}", gid), "Gid(2089b05ecca3d829)");
+    assert_eq!("2089b05ecca3d829".parse(), Ok(gid));
+    assert_eq!("2089B05ECCA3D829".parse(), Ok(gid));
+
+    assert_eq!(Gid::new(1).to_string(), "0000000000000001");
+    assert_eq!(Gid::new(1).to_string().parse(), Ok(Gid::new(1)));
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!(
+        "2089b05ecca3d82".parse::<Gid>(),
+        Err(GidParseError::InvalidLength(15))
+    );
+    assert_eq!(
+        "2089b05ecca3d829a".parse::<Gid>(),
+        Err(GidParseError::InvalidLength(17))
+    );
+    assert_eq!(
+        "2089b05ecca3d82g".parse::<Gid>(),
+        Err(GidParseError::InvalidCharacter('g'))
+    );
+    assert_eq!(
+        "+089b05ecca3d829".parse::<Gid>(),
+        Err(GidParseError::InvalidCharacter('+'))
+    );
+}
+
+#[test]
+fn null_and_ordering() {
+    assert!(Gid::NULL.is_null());
+    assert!(Gid::default().is_null());
+    assert!("0000000000000000".parse::<Gid>().unwrap().is_null());
+    assert!(!Gid::new(1).is_null());
+
+    let gids: BTreeSet<_> = vec![Gid::new(3), Gid::new(1), Gid::new(2)]
+        .into_iter()
+        .collect();
+    assert_eq!(
+        gids.into_iter().map(u64::from).collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+}
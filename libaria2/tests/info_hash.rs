@@ -0,0 +1,74 @@
+use libaria2::info_hash::{InfoHash, InfoHashParseError};
+use std::collections::HashSet;
+
+const HEX: &str = "c12fe1c06bba254a9dc9f519b335aa7c1367a88a";
+const BASE32: &str = "YEX6DQDLXISUVHOJ6UM3GNNKPQJWPKEK";
+
+#[test]
+fn from_raw_bytes() {
+    let bytes = (0..20).collect::<Vec<u8>>();
+    let hash = InfoHash::from_bytes(&bytes).unwrap();
+    assert_eq!(hash.as_bytes()[..], bytes[..]);
+    assert_eq!(hash.to_hex(), "000102030405060708090a0b0c0d0e0f10111213");
+
+    assert!(InfoHash::from_bytes(&[]).is_none());
+    assert!(InfoHash::from_bytes(&bytes[..19]).is_none());
+}
+
+#[test]
+fn hex_and_base32() {
+    let from_hex: InfoHash = HEX.parse().unwrap();
+    let from_base32: InfoHash = BASE32.parse().unwrap();
+    assert_eq!(from_hex, from_base32);
+    assert_eq!(from_hex.to_hex(), HEX);
+    assert_eq!(from_hex.to_base32(), BASE32);
+    assert_eq!(from_hex.to_string(), HEX);
+    assert_eq!(HEX.to_uppercase().parse::<InfoHash>().unwrap(), from_hex);
+
+    assert_eq!(
+        "abc".parse::<InfoHash>(),
+        Err(InfoHashParseError::InvalidLength(3))
+    );
+    assert_eq!(
+        HEX.replace('c', "z").parse::<InfoHash>(),
+        Err(InfoHashParseError::InvalidCharacter('z'))
+    );
+}
+
+#[test]
+fn magnet_xt() {
+    let expected: InfoHash = HEX.parse().unwrap();
+    assert_eq!(
+        InfoHash::from_magnet_xt(&format!("urn:btih:{}", HEX)),
+        Ok(expected)
+    );
+    assert_eq!(
+        InfoHash::from_magnet_xt(&format!("urn:btih:{}", BASE32)),
+        Ok(expected)
+    );
+    assert_eq!(
+        InfoHash::from_magnet_xt(&format!("urn:sha1:{}", HEX)),
+        Err(InfoHashParseError::NotBtih)
+    );
+
+    // Non-ASCII input must not be sliced inside a character.
+    assert_eq!(
+        InfoHash::from_magnet_xt("urn:btihé"),
+        Err(InfoHashParseError::NotBtih)
+    );
+    assert_eq!(
+        InfoHash::from_magnet_xt("ééééé"),
+        Err(InfoHashParseError::NotBtih)
+    );
+    assert_eq!(
+        InfoHash::from_magnet_xt("URN:BTIH:é"),
+        Err(InfoHashParseError::InvalidCharacter('é'))
+    );
+}
+
+#[test]
+fn usable_as_key() {
+    let mut set = HashSet::new();
+    set.insert(HEX.parse::<InfoHash>().unwrap());
+    assert!(set.contains(&BASE32.parse::<InfoHash>().unwrap()));
+}
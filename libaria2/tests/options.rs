@@ -0,0 +1,138 @@
+use libaria2::options::{
+    catalog::{self, OptionError},
+    FileAllocation, Options,
+};
+
+#[test]
+fn typed_setters() {
+    let options = Options::new()
+        .dir("/tmp")
+        .unwrap()
+        .split(4)
+        .max_download_limit(1024)
+        .file_allocation(FileAllocation::None)
+        .enable_dht(false)
+        .header("A: 1")
+        .header("B: 2");
+
+    assert_eq!(options.get("dir"), Some("/tmp"));
+    assert_eq!(options.get("split"), Some("4"));
+    assert_eq!(options.get("max-download-limit"), Some("1024"));
+    assert_eq!(options.get("file-allocation"), Some("none"));
+    assert_eq!(options.get("enable-dht"), Some("false"));
+    assert_eq!(options.iter().filter(|(k, _)| *k == "header").count(), 2);
+
+    let options = options.split(8);
+    assert_eq!(options.iter().filter(|(k, _)| *k == "split").count(), 1);
+    assert_eq!(options.get("split"), Some("8"));
+}
+
+#[test]
+fn validate_values() {
+    assert!(Options::new()
+        .split(4)
+        .min_split_size(1024 * 1024)
+        .validate()
+        .is_ok());
+    assert!(Options::new()
+        .set("min-split-size", "20M")
+        .validate()
+        .is_ok());
+
+    // Options missing from the catalog are left to aria2.
+    let unknown = Options::new()
+        .set("spilt", "4")
+        .split(4)
+        .set("no-want-digest-header", "true");
+    assert!(unknown.validate().is_ok());
+    assert!(unknown.validate_for_download().is_ok());
+    assert_eq!(
+        unknown.unknown_keys().collect::<Vec<_>>(),
+        ["spilt", "no-want-digest-header"]
+    );
+    assert!(matches!(
+        Options::new().max_connection_per_server(32).validate(),
+        Err(OptionError::InvalidValue { key, .. }) if key == "max-connection-per-server"
+    ));
+    assert!(matches!(
+        Options::new().set("file-allocation", "foo").validate(),
+        Err(OptionError::InvalidValue { .. })
+    ));
+
+    assert!(Options::new()
+        .checksum("sha-256", "0123abcDEF")
+        .validate()
+        .is_ok());
+    for checksum in ["not-a-checksum", "sha-256=", "sha-3=0123", "md5=xyz"] {
+        assert!(matches!(
+            Options::new().set("checksum", checksum).validate(),
+            Err(OptionError::InvalidValue { key, .. }) if key == "checksum"
+        ));
+    }
+}
+
+#[test]
+fn validate_scopes() {
+    assert_eq!(
+        Options::new().enable_dht(true).validate_for_download(),
+        Err(OptionError::NotPerDownload("enable-dht".to_string()))
+    );
+    assert_eq!(
+        Options::new()
+            .listen_port("6881")
+            .validate_for_global_change(),
+        Err(OptionError::NotChangeableAtRuntime(
+            "listen-port".to_string()
+        ))
+    );
+    assert_eq!(
+        Options::new().out("file").validate_for_global_change(),
+        Err(OptionError::NotChangeableAtRuntime("out".to_string()))
+    );
+    assert_eq!(
+        Options::new()
+            .set("piece-length", "2M")
+            .validate_for_download_change(),
+        Err(OptionError::NotChangeableAtRuntime(
+            "piece-length".to_string()
+        ))
+    );
+    assert!(Options::new()
+        .max_download_limit(10)
+        .validate_for_download_change()
+        .is_ok());
+    assert!(Options::new()
+        .max_overall_download_limit(10)
+        .validate_for_global_change()
+        .is_ok());
+
+    let spec = catalog::lookup("bt-max-peers").unwrap();
+    assert!(spec.is_per_download());
+    assert!(spec.is_changeable_while_active());
+    assert_eq!(spec.default, Some("55"));
+}
+
+#[cfg(unix)]
+#[test]
+fn non_utf8_paths() {
+    use libaria2::{errors::AriaError, request::DownloadOptions};
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt, path::Path};
+
+    // "café" in Latin-1.
+    let dir = Path::new(OsStr::from_bytes(b"/tmp/caf\xe9"));
+    assert!(matches!(
+        Options::new().dir(dir),
+        Err(AriaError::InvalidPath(p)) if p == dir
+    ));
+    assert!(matches!(
+        Options::new().log(dir.join("aria2.log")),
+        Err(AriaError::InvalidPath(_))
+    ));
+    assert!(matches!(
+        DownloadOptions::new().dir(dir),
+        Err(AriaError::InvalidPath(_))
+    ));
+
+    let options = Options::new().dir("/tmp/café").unwrap();
+    assert_eq!(options.get("dir"), Some("/tmp/café"));
+}
@@ -0,0 +1,126 @@
+use libaria2::{
+    download_handle::DownloadStatus, error_code::Aria2ErrorCode, events::DownloadEvent, gid::Gid,
+    progress::ProgressTracker, stats::DownloadStats,
+};
+use std::time::{Duration, Instant};
+
+fn stats(gid: Gid, completed: u64, total: u64, speed: u32) -> DownloadStats {
+    DownloadStats {
+        gid,
+        status: DownloadStatus::Active,
+        total_len: total,
+        completed_len: completed,
+        upload_len: 0,
+        download_speed: speed,
+        upload_speed: 0,
+        connections: 1,
+        error_code: Aria2ErrorCode::Finished,
+    }
+}
+
+fn secs(s: u64) -> Duration {
+    Duration::from_secs(s)
+}
+
+#[test]
+fn throttle() {
+    let start = Instant::now();
+
+    let mut disabled = ProgressTracker::default();
+    assert!(!disabled.is_due(start));
+
+    let mut tracker = ProgressTracker::new(Some(secs(2)), None);
+    assert!(tracker.is_due(start));
+    assert!(!tracker.is_due(start + secs(1)));
+    assert!(tracker.is_due(start + secs(2)));
+    assert!(!tracker.is_due(start + secs(3)));
+
+    // Stalls are checked every second even without progress events.
+    let mut stalls = ProgressTracker::new(None, Some(secs(60)));
+    assert!(stalls.is_due(start));
+    assert!(!stalls.is_due(start + Duration::from_millis(500)));
+    assert!(stalls.is_due(start + secs(1)));
+}
+
+#[test]
+fn progress_events() {
+    let gid = Gid::new(1);
+    let start = Instant::now();
+    let mut tracker = ProgressTracker::new(Some(secs(1)), None);
+
+    assert_eq!(
+        tracker.update(start, &[stats(gid, 10, 100, 5)]),
+        [DownloadEvent::Progress {
+            gid,
+            completed: 10,
+            total: 100,
+            speed: 5
+        }]
+    );
+    // Nothing new was received.
+    assert!(tracker
+        .update(start + secs(1), &[stats(gid, 10, 100, 0)])
+        .is_empty());
+
+    let mut without_interval = ProgressTracker::new(None, Some(secs(5)));
+    assert!(without_interval
+        .update(start, &[stats(gid, 10, 100, 5)])
+        .is_empty());
+}
+
+#[test]
+fn stall_fires_once_and_resets() {
+    let gid = Gid::new(1);
+    let start = Instant::now();
+    let mut tracker = ProgressTracker::new(None, Some(secs(5)));
+
+    assert!(tracker.update(start, &[stats(gid, 0, 100, 0)]).is_empty());
+    assert!(tracker
+        .update(start + secs(4), &[stats(gid, 0, 100, 0)])
+        .is_empty());
+    assert_eq!(
+        tracker.update(start + secs(5), &[stats(gid, 0, 100, 0)]),
+        [DownloadEvent::Stalled(gid)]
+    );
+    // Only once while it stays stalled.
+    assert!(tracker
+        .update(start + secs(20), &[stats(gid, 0, 100, 0)])
+        .is_empty());
+
+    // Moving again resets it.
+    assert!(tracker
+        .update(start + secs(21), &[stats(gid, 10, 100, 0)])
+        .is_empty());
+    assert!(tracker
+        .update(start + secs(25), &[stats(gid, 10, 100, 0)])
+        .is_empty());
+    assert_eq!(
+        tracker.update(start + secs(26), &[stats(gid, 10, 100, 0)]),
+        [DownloadEvent::Stalled(gid)]
+    );
+}
+
+#[test]
+fn finished_and_removed_downloads() {
+    let seeding = Gid::new(1);
+    let other = Gid::new(2);
+    let start = Instant::now();
+    let mut tracker = ProgressTracker::new(None, Some(secs(5)));
+
+    // A seeding torrent doesn't receive anything but isn't stalled.
+    tracker.update(start, &[stats(seeding, 100, 100, 0)]);
+    assert!(tracker
+        .update(start + secs(10), &[stats(seeding, 100, 100, 0)])
+        .is_empty());
+
+    // A download that left the active ones starts over when it comes back.
+    tracker.update(start, &[stats(other, 0, 100, 0)]);
+    tracker.update(start + secs(4), &[]);
+    assert!(tracker
+        .update(start + secs(6), &[stats(other, 0, 100, 0)])
+        .is_empty());
+    assert_eq!(
+        tracker.update(start + secs(11), &[stats(other, 0, 100, 0)]),
+        [DownloadEvent::Stalled(other)]
+    );
+}
@@ -0,0 +1,68 @@
+use libaria2::prelude::*;
+use libaria2_sys::ffi;
+
+fn key_vals(options: &DownloadOptions) -> Vec<(String, String)> {
+    Vec::<ffi::KeyVal>::from(options.as_ref())
+        .into_iter()
+        .map(|kv| (kv.key, kv.val))
+        .collect()
+}
+
+fn pair(key: &str, val: &str) -> (String, String) {
+    (key.to_string(), val.to_string())
+}
+
+#[test]
+fn download_options() {
+    let options = DownloadOptions::new()
+        .out("file.iso")
+        .dir("/tmp/downloads")
+        .unwrap()
+        .header("A: 1")
+        .header("B: 2")
+        .checksum("sha-1", "0123456789abcdef")
+        .out("other.iso");
+
+    assert_eq!(
+        key_vals(&options),
+        [
+            pair("dir", "/tmp/downloads"),
+            pair("header", "A: 1"),
+            pair("header", "B: 2"),
+            pair("checksum", "sha-1=0123456789abcdef"),
+            pair("out", "other.iso"),
+        ]
+    );
+}
+
+#[test]
+fn download_options_merge() {
+    let base = Options::new().split(4).header("A: 1");
+    let options = DownloadOptions::new()
+        .options(&base)
+        .option("split", "8")
+        .position(2);
+
+    assert_eq!(
+        key_vals(&options),
+        [pair("header", "A: 1"), pair("split", "8")]
+    );
+    assert_eq!(DownloadOptions::from(base.clone()).as_ref(), &base);
+}
+
+#[test]
+fn download_request() {
+    let request = DownloadRequest::new("http://a/file")
+        .mirror("http://b/file")
+        .mirror("ftp://c/file")
+        .options(DownloadOptions::new().out("file"));
+
+    assert_eq!(
+        request.uris(),
+        ["http://a/file", "http://b/file", "ftp://c/file"]
+    );
+    assert_eq!(
+        DownloadRequest::from("http://a/file").uris(),
+        ["http://a/file"]
+    );
+}